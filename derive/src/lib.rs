@@ -0,0 +1,224 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, LitInt, LitStr, Result, Type};
+
+/// Derives `Serialisable` by writing and reading each field in declaration order
+///
+/// Fields are written with their own `Serialisable` impl unless an attribute chooses otherwise:
+///
+/// - `#[oaf(varint)]` writes an integer as a LEB128 varint rather than at its fixed width
+/// - `#[oaf(len = "u16")]` or `#[oaf(len = "varint")]` fixes how the length of a `String` or
+///   `Vec` is written, rather than following the format version
+///
+/// Enum variants are written as a one byte tag followed by their fields, and every variant needs
+/// its tag given with `#[oaf(tag = 0)]`
+#[proc_macro_derive(Serialisable, attributes(oaf))]
+pub fn derive_serialisable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// How a single field is written
+enum Encoding {
+    /// With the field's own `Serialisable` impl
+    Default,
+    /// As a varint, for integers
+    Varint,
+    /// With the given `LengthEncoding` variant, for strings and vectors
+    Length(TokenStream2),
+}
+
+/// Reads the `#[oaf(...)]` attributes of a field
+fn field_encoding(attrs: &[Attribute]) -> Result<Encoding> {
+    let mut encoding = Encoding::Default;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("oaf")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("varint") {
+                encoding = Encoding::Varint;
+                Ok(())
+            } else if meta.path.is_ident("len") {
+                let lit: LitStr = meta.value()?.parse()?;
+                encoding = match lit.value().as_str() {
+                    "u16" => Encoding::Length(quote!(U16)),
+                    "varint" => Encoding::Length(quote!(Varint)),
+                    _ => return Err(Error::new_spanned(lit, "expected \"u16\" or \"varint\"")),
+                };
+                Ok(())
+            } else {
+                Err(meta.error("expected `varint` or `len`"))
+            }
+        })?;
+    }
+    Ok(encoding)
+}
+
+/// Reads the `#[oaf(tag = ...)]` attribute of an enum variant
+fn variant_tag(attrs: &[Attribute], variant: &syn::Variant) -> Result<u8> {
+    let mut tag = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("oaf")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("tag") {
+                let lit: LitInt = meta.value()?.parse()?;
+                tag = Some(lit.base10_parse::<u8>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `tag`"))
+            }
+        })?;
+    }
+    tag.ok_or_else(|| Error::new_spanned(variant, "enum variants need a tag: #[oaf(tag = 0)]"))
+}
+
+/// Returns the statement writing the field bound to `value`, a reference
+fn write_field(value: &TokenStream2, encoding: &Encoding) -> TokenStream2 {
+    match encoding {
+        Encoding::Default => quote! {
+            ::open_archery_format::serialise::Serialisable::serialise_into(#value, __oaf_res)?;
+        },
+        Encoding::Varint => quote! {
+            __oaf_res.append_varint(::open_archery_format::serialise::Varint::to_varint(#value));
+        },
+        Encoding::Length(lengths) => quote! {
+            ::open_archery_format::serialise::Sequence::serialise_with(
+                #value,
+                __oaf_res,
+                ::open_archery_format::buffer::LengthEncoding::#lengths,
+            )?;
+        },
+    }
+}
+
+/// Returns the expression reading a field of type `ty`
+fn read_field(ty: &Type, encoding: &Encoding) -> TokenStream2 {
+    match encoding {
+        Encoding::Default => quote! {
+            <#ty as ::open_archery_format::serialise::Serialisable>::deserialise(__oaf_data)?
+        },
+        Encoding::Varint => quote! {
+            <#ty as ::open_archery_format::serialise::Varint>::from_varint(__oaf_data.pop_varint()?)?
+        },
+        Encoding::Length(lengths) => quote! {
+            <#ty as ::open_archery_format::serialise::Sequence>::deserialise_with(
+                __oaf_data,
+                ::open_archery_format::buffer::LengthEncoding::#lengths,
+            )?
+        },
+    }
+}
+
+/// Returns the pattern destructuring `fields`, the statements writing them, and the constructor
+/// reading them back, for the struct or variant at `path`
+fn expand_fields(path: TokenStream2, fields: &Fields) -> Result<(TokenStream2, TokenStream2, TokenStream2)> {
+    let mut idents = vec![];
+    let mut bindings = vec![];
+    let mut writes = vec![];
+    let mut reads = vec![];
+
+    for (i, field) in fields.iter().enumerate() {
+        let encoding = field_encoding(&field.attrs)?;
+        // bound under a name no field or parameter of the generated code can share
+        let binding = format_ident!("__oaf_field_{}", i);
+
+        writes.push(write_field(&quote!(#binding), &encoding));
+        reads.push(read_field(&field.ty, &encoding));
+        idents.extend(field.ident.clone());
+        bindings.push(binding);
+    }
+
+    Ok(match fields {
+        Fields::Named(_) => (
+            quote!(#path { #(#idents: #bindings),* }),
+            quote!(#(#writes)*),
+            quote!(#path { #(#idents: #reads),* }),
+        ),
+        Fields::Unnamed(_) => (
+            quote!(#path ( #(#bindings),* )),
+            quote!(#(#writes)*),
+            quote!(#path ( #(#reads),* )),
+        ),
+        Fields::Unit => (quote!(#path), quote!(), quote!(#path)),
+    })
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let (serialise, deserialise) = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, writes, read) = expand_fields(quote!(Self), &data.fields)?;
+            (
+                quote! {
+                    let #pattern = self;
+                    #writes
+                },
+                quote!(Ok(#read)),
+            )
+        },
+        Data::Enum(data) => {
+            let mut tags = vec![];
+            let mut serialise_arms = vec![];
+            let mut deserialise_arms = vec![];
+
+            for variant in &data.variants {
+                let tag = variant_tag(&variant.attrs, variant)?;
+                if tags.contains(&tag) {
+                    return Err(Error::new_spanned(variant, format!("tag {tag} is used twice")));
+                }
+                tags.push(tag);
+
+                let ident = &variant.ident;
+                let (pattern, writes, read) = expand_fields(quote!(Self::#ident), &variant.fields)?;
+                serialise_arms.push(quote! {
+                    #pattern => {
+                        __oaf_res.append_u8(#tag);
+                        #writes
+                    },
+                });
+                deserialise_arms.push(quote!(#tag => Ok(#read),));
+            }
+
+            (
+                quote! {
+                    match self {
+                        #(#serialise_arms)*
+                    }
+                },
+                quote! {
+                    let __oaf_offset = __oaf_data.position();
+                    match __oaf_data.pop_u8()? {
+                        #(#deserialise_arms)*
+                        __oaf_tag => Err(::open_archery_format::buffer::BufferError::UnknownTag {
+                            tag: __oaf_tag,
+                            offset: __oaf_offset,
+                        }),
+                    }
+                },
+            )
+        },
+        Data::Union(data) => {
+            return Err(Error::new_spanned(data.union_token, "unions cannot derive Serialisable"));
+        },
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::open_archery_format::serialise::Serialisable for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn serialise_into(
+                &self,
+                __oaf_res: &mut ::open_archery_format::buffer::Buffer,
+            ) -> ::std::result::Result<(), ::open_archery_format::buffer::BufferError> {
+                #serialise
+                Ok(())
+            }
+
+            #[allow(unused_variables)]
+            fn deserialise(
+                __oaf_data: &mut ::open_archery_format::buffer::Reader,
+            ) -> ::std::result::Result<Self, ::open_archery_format::buffer::BufferError> {
+                #deserialise
+            }
+        }
+    })
+}
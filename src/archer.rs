@@ -0,0 +1,115 @@
+use crate::Serialisable;
+
+/// The person whose scores a Session holds
+#[derive(Serialisable, Debug, PartialEq)]
+pub struct Archer {
+    pub name: String,
+    pub club: Option<String>,
+    /// Membership or licence number with the archer's governing body
+    pub membership_number: Option<String>,
+    pub age: Option<Age>,
+    pub gender: Option<GenderCategory>,
+    pub handedness: Option<Handedness>,
+    pub para_classification: Option<ParaClassification>,
+}
+
+impl Archer {
+    /// Returns an Archer with the given name and nothing else recorded
+    pub fn new(name: String) -> Archer {
+        Archer {
+            name,
+            club: None,
+            membership_number: None,
+            age: None,
+            gender: None,
+            handedness: None,
+            para_classification: None,
+        }
+    }
+}
+
+#[derive(Serialisable, Debug, PartialEq)]
+pub enum Age {
+    /// Date of birth as written by the archer's app, usually YYYY-MM-DD
+    #[oaf(tag = 0)]
+    DateOfBirth(String),
+    #[oaf(tag = 1)]
+    Category(AgeCategory),
+}
+
+#[derive(Serialisable, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AgeCategory {
+    #[oaf(tag = 0)]
+    Under12,
+    #[oaf(tag = 1)]
+    Under14,
+    #[oaf(tag = 2)]
+    Under15,
+    #[oaf(tag = 3)]
+    Under16,
+    #[oaf(tag = 4)]
+    Under18,
+    #[oaf(tag = 5)]
+    Under21,
+    #[oaf(tag = 6)]
+    Adult,
+    #[oaf(tag = 7)]
+    Over50,
+    #[oaf(tag = 8)]
+    Over60,
+    #[oaf(tag = 9)]
+    Over70,
+}
+
+#[derive(Serialisable, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GenderCategory {
+    #[oaf(tag = 0)]
+    Female,
+    #[oaf(tag = 1)]
+    Male,
+    #[oaf(tag = 2)]
+    Open,
+}
+
+#[derive(Serialisable, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Handedness {
+    #[oaf(tag = 0)]
+    Right,
+    #[oaf(tag = 1)]
+    Left,
+}
+
+#[derive(Serialisable, Debug, PartialEq)]
+pub enum ParaClassification {
+    /// Wheelchair, impaired in the arms as well as the legs
+    #[oaf(tag = 0)]
+    W1,
+    /// Wheelchair or standing, impaired in the legs or one side
+    #[oaf(tag = 1)]
+    Open,
+    #[oaf(tag = 2)]
+    VisuallyImpaired1,
+    #[oaf(tag = 3)]
+    VisuallyImpaired23,
+    /// A classification from another governing body
+    #[oaf(tag = 4)]
+    Other(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archer() {
+        let archer = Archer {
+            club: Some("St Andrews".to_string()),
+            age: Some(Age::Category(AgeCategory::Under18)),
+            handedness: Some(Handedness::Left),
+            para_classification: Some(ParaClassification::Other("ST".to_string())),
+            ..Archer::new("Robin".to_string())
+        };
+
+        assert_eq!(archer, Archer::deserialise(&mut archer.serialise().unwrap().reader()).unwrap());
+    }
+}
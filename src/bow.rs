@@ -0,0 +1,73 @@
+use crate::Serialisable;
+
+/// The equipment a Session was shot with
+#[derive(Serialisable, Debug, PartialEq)]
+pub struct Bow {
+    pub style: BowStyle,
+    /// Draw weight at the archer's draw length, in pounds
+    pub draw_weight: Option<f32>,
+    /// Draw length, in inches
+    pub draw_length: Option<f32>,
+    /// Brace height, in inches
+    pub brace_height: Option<f32>,
+    pub riser: Option<String>,
+    pub limbs: Option<String>,
+    pub sight: Option<String>,
+    pub stabilisation: Option<String>,
+    pub release_aid: Option<String>,
+}
+
+impl Bow {
+    /// Returns a Bow of the given style with nothing else recorded
+    pub fn new(style: BowStyle) -> Bow {
+        Bow {
+            style,
+            draw_weight: None,
+            draw_length: None,
+            brace_height: None,
+            riser: None,
+            limbs: None,
+            sight: None,
+            stabilisation: None,
+            release_aid: None,
+        }
+    }
+}
+
+#[derive(Serialisable, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BowStyle {
+    #[oaf(tag = 0)]
+    Recurve,
+    #[oaf(tag = 1)]
+    Compound,
+    #[oaf(tag = 2)]
+    Barebow,
+    #[oaf(tag = 3)]
+    Longbow,
+    #[oaf(tag = 4)]
+    Traditional,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bow() {
+        let bow = Bow {
+            draw_weight: Some(38.5),
+            draw_length: Some(28.0),
+            sight: Some("Shibuya Ultima".to_string()),
+            ..Bow::new(BowStyle::Recurve)
+        };
+
+        assert_eq!(bow, Bow::deserialise(&mut bow.serialise().unwrap().reader()).unwrap());
+    }
+
+    #[test]
+    fn test_bow_style_serialise() {
+        let data = Bow::new(BowStyle::Barebow).serialise().unwrap();
+
+        assert_eq!(data, vec![2, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+}
@@ -1,10 +1,52 @@
-use std::string::FromUtf8Error;
+use std::str::{self, Utf8Error};
+use crate::version::Version;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum BufferError {
     BufferEmpty,
-    InvalidUtf8String(FromUtf8Error),
+    InvalidUtf8String(Utf8Error),
     UsizeTooBig,
+    /// A variable length integer ran past 64 bits, or past the width of the integer read into
+    VarintTooLong,
+    /// An End started with a type tag this library does not know, at the given offset from the
+    /// start of the Reader
+    UnknownEndType {
+        tag: u8,
+        offset: usize,
+    },
+    /// A chunk with the given tag is required but was not found
+    MissingChunk([u8; 4]),
+    /// A chunk tag reserved for this library was used for custom data
+    ReservedTag([u8; 4]),
+    /// An enum started with a tag none of its variants use, at the given offset from the start
+    /// of the Reader
+    UnknownTag {
+        tag: u8,
+        offset: usize,
+    },
+    /// A date, time or UTC offset was out of range, at the given offset from the start of the
+    /// Reader
+    InvalidDateTime {
+        offset: usize,
+    },
+    /// A ScoreValue was worth more points than its one byte encoding can hold
+    ScoreTooBig(u8),
+    /// A Target recorded end times, but not one for each of its ends
+    EndTimesMismatch {
+        ends: usize,
+        times: usize,
+    },
+}
+
+/// How a length or count is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthEncoding {
+    /// As the format version of the Buffer or Reader decides, see `append_usize`
+    Version,
+    /// Always as a u16
+    U16,
+    /// Always as a varint
+    Varint,
 }
 
 /// Internal method for converting the lower two bytes of a usize to a Vec<u8>
@@ -16,9 +58,27 @@ fn usize_as_u16_as_bytes(len: usize) -> Result<Vec<u8>, BufferError> {
     }
 }
 
+/// Internal method for converting a u64 to its LEB128 encoding, seven bits per byte with the
+/// high bit set on every byte but the last
+fn u64_as_varint_bytes(mut n: u64) -> Vec<u8> {
+    let mut res = vec![];
+    loop {
+        let b = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            res.push(b);
+            return res;
+        }
+        res.push(b | 0x80);
+    }
+}
+
+/// A growable buffer that values are appended to when serialising
 #[derive(Debug)]
 pub struct Buffer {
     v: Vec<u8>,
+    /// Format version being written, which decides how lengths are appended
+    version: Version,
 }
 
 impl PartialEq<Self> for Buffer {
@@ -33,11 +93,23 @@ impl PartialEq<Vec<u8>> for Buffer {
     }
 }
 
+impl Default for Buffer {
+    fn default() -> Self {
+        Buffer::new()
+    }
+}
+
 impl Buffer {
-    /// Returns a Buffer with an empty underlying Vec<u8>
+    /// Returns a Buffer with an empty underlying Vec<u8>, writing the current format version
     pub fn new() -> Buffer {
+        Buffer::with_version(Version::CURRENT)
+    }
+
+    /// Returns a Buffer with an empty underlying Vec<u8>, writing the given format version
+    pub fn with_version(version: Version) -> Buffer {
         Buffer {
             v: vec!(),
+            version,
         }
     }
 
@@ -45,23 +117,66 @@ impl Buffer {
     pub fn from(v: Vec<u8>) -> Buffer {
         Buffer {
             v,
+            version: Version::CURRENT,
         }
     }
 
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
     pub fn length(&self) -> usize {
         self.v.len()
     }
 
+    /// Returns the bytes appended so far
+    pub fn bytes(&self) -> &[u8] {
+        &self.v
+    }
+
     pub fn take_underlying_buffer(self) -> Vec<u8> {
         self.v
     }
 
-    /// Appends the lower two bytes of the given usize to the Buffer
+    /// Returns a Reader over the bytes appended so far, in the same format version
+    pub fn reader(&self) -> Reader<'_> {
+        Reader::with_version(&self.v, self.version)
+    }
+
+    /// Appends a length or count to the Buffer, as a u16 before format 0.2.0 and a varint since
     pub fn append_usize(&mut self, len: usize) -> Result<(), BufferError> {
+        if self.version < Version::V0_2_0 {
+            self.append_usize_u16(len)
+        } else {
+            self.append_varint(len as u64);
+            Ok(())
+        }
+    }
+
+    /// Appends the lower two bytes of the given usize to the Buffer, as lengths were written
+    /// before format 0.2.0
+    pub fn append_usize_u16(&mut self, len: usize) -> Result<(), BufferError> {
         self.v.append(&mut usize_as_u16_as_bytes(len)?);
         Ok(())
     }
 
+    /// Appends a length or count with the given encoding
+    pub fn append_len(&mut self, len: usize, lengths: LengthEncoding) -> Result<(), BufferError> {
+        match lengths {
+            LengthEncoding::Version => self.append_usize(len),
+            LengthEncoding::U16 => self.append_usize_u16(len),
+            LengthEncoding::Varint => {
+                self.append_varint(len as u64);
+                Ok(())
+            },
+        }
+    }
+
+    /// Appends the given u64 as a LEB128 variable length integer
+    pub fn append_varint(&mut self, n: u64) {
+        self.v.append(&mut u64_as_varint_bytes(n));
+    }
+
     pub fn append_string(&mut self, str: &String) -> Result<(), BufferError> {
         let b = str.as_bytes();
         self.append_usize(b.len())?;
@@ -73,10 +188,35 @@ impl Buffer {
         self.v.append(&mut b.v);
     }
 
+    pub fn append_bytes(&mut self, b: &[u8]) {
+        self.v.extend_from_slice(b);
+    }
+
+    /// Appends a chunk: the four byte tag, then the length of the contents as a varint, then the
+    /// contents as written by `f`
+    ///
+    /// Readers can skip a whole chunk whose tag they do not know
+    pub fn append_chunk<F>(&mut self, tag: [u8; 4], f: F) -> Result<(), BufferError>
+    where
+        F: FnOnce(&mut Buffer) -> Result<(), BufferError>,
+    {
+        let mut contents = Buffer::with_version(self.version);
+        f(&mut contents)?;
+
+        self.append_bytes(&tag);
+        self.append_varint(contents.length() as u64);
+        self.append(&mut contents);
+        Ok(())
+    }
+
     pub fn append_u16(&mut self, n: u16) {
         self.v.extend_from_slice(&n.to_le_bytes());
     }
 
+    pub fn append_i16(&mut self, n: i16) {
+        self.v.extend_from_slice(&n.to_le_bytes());
+    }
+
     pub fn append_u32(&mut self, n: u32) {
         self.v.extend_from_slice(&n.to_le_bytes());
     }
@@ -92,57 +232,188 @@ impl Buffer {
     pub fn append_u8(&mut self, n: u8) {
         self.v.extend_from_slice(&n.to_le_bytes());
     }
+}
+
+/// A read position over borrowed bytes that values are popped from when deserialising
+///
+/// Popping only moves the position forward, so no bytes are copied or shifted
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+    v: &'a [u8],
+    pos: usize,
+    /// Index just past the last byte this Reader may pop
+    end: usize,
+    /// Format version the bytes were written with, which decides how lengths are read
+    version: Version,
+    /// Whether records of kinds this library does not know are kept rather than rejected
+    lenient: bool,
+}
 
-    pub fn pop_n_bytes(&mut self, n: usize) -> Result<Vec<u8>, BufferError> {
-        let mut res = vec![];
+impl<'a> Reader<'a> {
+    /// Returns a Reader positioned at the start of the given bytes, written with the current
+    /// format version
+    pub fn new(v: &'a [u8]) -> Reader<'a> {
+        Reader::with_version(v, Version::CURRENT)
+    }
 
-        for i in 0..n {
-            let ob = self.v.get(i);
-            match ob {
-                None => return Err(BufferError::BufferEmpty),
-                Some(b) => res.push(*b),
-            }
+    /// Returns a Reader positioned at the start of the given bytes, written with the given format
+    /// version
+    pub fn with_version(v: &'a [u8], version: Version) -> Reader<'a> {
+        Reader {
+            v,
+            pos: 0,
+            end: v.len(),
+            version,
+            lenient: false,
+        }
+    }
+
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    pub fn set_version(&mut self, version: Version) {
+        self.version = version;
+    }
+
+    pub fn lenient(&self) -> bool {
+        self.lenient
+    }
+
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    /// Returns the number of bytes popped from the Reader so far
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the number of bytes left to pop
+    pub fn remaining(&self) -> usize {
+        self.end - self.pos
+    }
+
+    /// Returns the bytes left to pop without moving the position
+    pub fn rest(&self) -> &'a [u8] {
+        &self.v[self.pos..self.end]
+    }
+
+    pub fn pop_n_bytes(&mut self, n: usize) -> Result<&'a [u8], BufferError> {
+        if n > self.remaining() {
+            return Err(BufferError::BufferEmpty);
         }
-        self.v = self.v.split_at(res.len()).1.to_vec();
+
+        let res = &self.v[self.pos..self.pos + n];
+        self.pos += n;
         Ok(res)
     }
 
+    /// Pops the next n bytes as a Reader of their own
+    ///
+    /// Positions in the returned Reader still count from the start of this one, so offsets in
+    /// errors point into the whole input
+    pub fn pop_reader(&mut self, n: usize) -> Result<Reader<'a>, BufferError> {
+        if n > self.remaining() {
+            return Err(BufferError::BufferEmpty);
+        }
+
+        let res = Reader {
+            v: self.v,
+            pos: self.pos,
+            end: self.pos + n,
+            version: self.version,
+            lenient: self.lenient,
+        };
+        self.pos += n;
+        Ok(res)
+    }
+
+    /// Pops a chunk, returning its tag and a Reader over its contents
+    pub fn pop_chunk(&mut self) -> Result<([u8; 4], Reader<'a>), BufferError> {
+        let tag = self.pop_array()?;
+        let len = usize::try_from(self.pop_varint()?).map_err(|_| BufferError::UsizeTooBig)?;
+
+        Ok((tag, self.pop_reader(len)?))
+    }
+
+    /// Internal method for popping a fixed number of bytes as an array
+    fn pop_array<const N: usize>(&mut self) -> Result<[u8; N], BufferError> {
+        Ok(self.pop_n_bytes(N)?.try_into().unwrap())
+    }
+
+    /// Pops a length or count, as a u16 before format 0.2.0 and a varint since
     pub fn pop_usize(&mut self) -> Result<usize, BufferError> {
-        let bytes = self.pop_n_bytes(2)?;
-        Ok(u16::from_le_bytes(bytes.try_into().unwrap()) as usize)
+        if self.version < Version::V0_2_0 {
+            self.pop_usize_u16()
+        } else {
+            usize::try_from(self.pop_varint()?).map_err(|_| BufferError::UsizeTooBig)
+        }
     }
 
-    pub fn pop_string(&mut self) -> Result<String, BufferError> {
-        let len = self.pop_usize()?;
-        match String::from_utf8(self.pop_n_bytes(len)?) {
-            Ok(str) => Ok(str),
-            Err(e) => Err(BufferError::InvalidUtf8String(e)),
+    pub fn pop_usize_u16(&mut self) -> Result<usize, BufferError> {
+        Ok(u16::from_le_bytes(self.pop_array()?) as usize)
+    }
+
+    /// Pops a length or count with the given encoding
+    pub fn pop_len(&mut self, lengths: LengthEncoding) -> Result<usize, BufferError> {
+        match lengths {
+            LengthEncoding::Version => self.pop_usize(),
+            LengthEncoding::U16 => self.pop_usize_u16(),
+            LengthEncoding::Varint => usize::try_from(self.pop_varint()?).map_err(|_| BufferError::UsizeTooBig),
+        }
+    }
+
+    /// Pops a LEB128 variable length integer
+    pub fn pop_varint(&mut self) -> Result<u64, BufferError> {
+        let mut res = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.pop_u8()?;
+            let bits = (b & 0x7f) as u64;
+            if bits << shift >> shift != bits {
+                return Err(BufferError::VarintTooLong);
+            }
+
+            res |= bits << shift;
+            if b & 0x80 == 0 {
+                return Ok(res);
+            }
         }
+        Err(BufferError::VarintTooLong)
+    }
+
+    /// Pops a string borrowed from the underlying bytes
+    pub fn pop_str(&mut self) -> Result<&'a str, BufferError> {
+        let len = self.pop_usize()?;
+        str::from_utf8(self.pop_n_bytes(len)?).map_err(BufferError::InvalidUtf8String)
+    }
+
+    pub fn pop_string(&mut self) -> Result<String, BufferError> {
+        Ok(self.pop_str()?.to_string())
     }
 
     pub fn pop_u16(&mut self) -> Result<u16, BufferError> {
-        let bytes = self.pop_n_bytes(2)?;
-        Ok(u16::from_le_bytes(bytes.try_into().unwrap()))
+        Ok(u16::from_le_bytes(self.pop_array()?))
+    }
+
+    pub fn pop_i16(&mut self) -> Result<i16, BufferError> {
+        Ok(i16::from_le_bytes(self.pop_array()?))
     }
 
     pub fn pop_u32(&mut self) -> Result<u32, BufferError> {
-        let bytes = self.pop_n_bytes(4)?;
-        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+        Ok(u32::from_le_bytes(self.pop_array()?))
     }
 
     pub fn pop_u64(&mut self) -> Result<u64, BufferError> {
-        let bytes = self.pop_n_bytes(8)?;
-        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+        Ok(u64::from_le_bytes(self.pop_array()?))
     }
 
     pub fn pop_f32(&mut self) -> Result<f32, BufferError> {
-        let bytes = self.pop_n_bytes(4)?;
-        Ok(f32::from_le_bytes(bytes.try_into().unwrap()))
+        Ok(f32::from_le_bytes(self.pop_array()?))
     }
 
     pub fn pop_u8(&mut self) -> Result<u8, BufferError> {
-        let bytes = self.pop_n_bytes(1)?;
-        Ok(u8::from_le_bytes(bytes.try_into().unwrap()))
+        Ok(u8::from_le_bytes(self.pop_array()?))
     }
 }
 
@@ -155,7 +426,47 @@ mod buffer_tests {
         let mut buf = Buffer::new();
         buf.append_usize(5).unwrap();
 
-        assert_eq!(5, buf.pop_usize().unwrap());
+        assert_eq!(5, buf.reader().pop_usize().unwrap());
+    }
+
+    #[test]
+    fn test_buffer_usize_large() {
+        let mut buf = Buffer::new();
+        buf.append_usize(100_000).unwrap();
+
+        assert_eq!(buf, vec![0xa0, 0x8d, 0x06]);
+        assert_eq!(100_000, buf.reader().pop_usize().unwrap());
+    }
+
+    #[test]
+    fn test_buffer_usize_u16() {
+        let mut buf = Buffer::with_version(Version::V0_1_0);
+        buf.append_usize(5).unwrap();
+        assert_eq!(Err(BufferError::UsizeTooBig), buf.append_usize(100_000));
+
+        assert_eq!(buf, vec![5, 0]);
+        assert_eq!(5, buf.reader().pop_usize().unwrap());
+    }
+
+    #[test]
+    fn test_buffer_varint() {
+        for n in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut buf = Buffer::new();
+            buf.append_varint(n);
+
+            assert_eq!(n, buf.reader().pop_varint().unwrap());
+        }
+
+        let mut buf = Buffer::new();
+        buf.append_varint(u64::MAX);
+        assert_eq!(10, buf.length());
+    }
+
+    #[test]
+    fn test_buffer_varint_too_long() {
+        assert_eq!(Err(BufferError::VarintTooLong), Reader::new(&[0xff; 11]).pop_varint());
+        assert_eq!(Err(BufferError::VarintTooLong), Reader::new(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]).pop_varint());
+        assert_eq!(Err(BufferError::BufferEmpty), Reader::new(&[0x80]).pop_varint());
     }
 
     #[test]
@@ -163,7 +474,17 @@ mod buffer_tests {
         let mut buf = Buffer::new();
         buf.append_string(&"test".to_string()).unwrap();
 
-        assert_eq!("test", buf.pop_string().unwrap());
+        assert_eq!("test", buf.reader().pop_string().unwrap());
+    }
+
+    #[test]
+    fn test_buffer_str_borrows() {
+        let data = vec![4, 116, 101, 115, 116];
+        let mut reader = Reader::new(&data);
+
+        let s = reader.pop_str().unwrap();
+        assert_eq!("test", s);
+        assert_eq!(data[1..].as_ptr(), s.as_ptr());
     }
 
     #[test]
@@ -171,7 +492,7 @@ mod buffer_tests {
         let mut buf = Buffer::new();
         buf.append_u32(7);
 
-        assert_eq!(7, buf.pop_u32().unwrap());
+        assert_eq!(7, buf.reader().pop_u32().unwrap());
     }
 
     #[test]
@@ -179,7 +500,7 @@ mod buffer_tests {
         let mut buf = Buffer::new();
         buf.append_f32(7.0);
 
-        assert_eq!(7.0, buf.pop_f32().unwrap());
+        assert_eq!(7.0, buf.reader().pop_f32().unwrap());
     }
 
     #[test]
@@ -187,18 +508,70 @@ mod buffer_tests {
         let mut buf = Buffer::new();
         buf.append_u8(15);
 
-        assert_eq!(15, buf.pop_u8().unwrap());
+        assert_eq!(15, buf.reader().pop_u8().unwrap());
     }
 
     #[test]
     fn test_buffer_buffer() {
         let mut buf = Buffer::new();
-        let mut other = Buffer {
-            v: vec![1,2,3,4],
-        };
+        let mut other = Buffer::from(vec![1,2,3,4]);
 
         buf.append(&mut other);
 
-        assert_eq!(vec![1,2,3], buf.pop_n_bytes(3).unwrap());
+        assert_eq!(&[1,2,3], buf.reader().pop_n_bytes(3).unwrap());
+    }
+
+    #[test]
+    fn test_buffer_chunk() {
+        let mut buf = Buffer::new();
+        buf.append_chunk(*b"TEST", |buf| {
+            buf.append_u8(7);
+            buf.append_u16(8);
+            Ok(())
+        }).unwrap();
+        buf.append_u8(9);
+
+        assert_eq!(buf, vec![84, 69, 83, 84, 3, 7, 8, 0, 9]);
+
+        let mut reader = buf.reader();
+        let (tag, mut chunk) = reader.pop_chunk().unwrap();
+        assert_eq!(*b"TEST", tag);
+        assert_eq!(7, chunk.pop_u8().unwrap());
+        assert_eq!(8, chunk.pop_u16().unwrap());
+        assert_eq!(0, chunk.remaining());
+        assert_eq!(9, reader.pop_u8().unwrap());
+    }
+
+    #[test]
+    fn test_buffer_position() {
+        let data = [1,2,3,4,5,6];
+        let mut reader = Reader::new(&data);
+        assert_eq!(0, reader.position());
+
+        reader.pop_u8().unwrap();
+        reader.pop_u32().unwrap();
+        assert_eq!(5, reader.position());
+        assert_eq!(1, reader.remaining());
+
+        assert_eq!(Err(BufferError::BufferEmpty), reader.pop_u16());
+        assert_eq!(5, reader.position());
+    }
+
+    #[test]
+    fn test_buffer_pop_reader() {
+        let data = [1,2,3,4,5,6];
+        let mut reader = Reader::new(&data);
+        reader.pop_u8().unwrap();
+
+        let mut inner = reader.pop_reader(3).unwrap();
+        assert_eq!(4, reader.position());
+        assert_eq!(&[5, 6], reader.rest());
+
+        assert_eq!(1, inner.position());
+        assert_eq!(&[2, 3, 4], inner.rest());
+        assert_eq!(2, inner.pop_u8().unwrap());
+        assert_eq!(Err(BufferError::BufferEmpty), inner.pop_u32());
+
+        assert_eq!(Err(BufferError::BufferEmpty), reader.pop_reader(3).map(|r| r.remaining()));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,36 @@
+/// CRC-32C (Castagnoli) polynomial, bit reversed
+const POLYNOMIAL: u32 = 0x82f6_3b78;
+
+/// Lookup table of the checksum of every byte value
+const TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ POLYNOMIAL } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Returns the CRC-32C checksum of the given bytes, as written after the body of a file
+pub fn crc32c(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, b| TABLE[((crc ^ *b as u32) & 0xff) as usize] ^ (crc >> 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32c() {
+        assert_eq!(0, crc32c(b""));
+        assert_eq!(0xe306_9283, crc32c(b"123456789"));
+        assert_eq!(0x8a91_36aa, crc32c(&[0; 32]));
+    }
+}
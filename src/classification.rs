@@ -0,0 +1,606 @@
+use crate::archer::{Age, AgeCategory, GenderCategory};
+use crate::bow::BowStyle;
+use crate::date::Date;
+use crate::face::FaceKind;
+use crate::handicap;
+use crate::rounds::{self, RoundDefinition};
+use crate::session::{Round, Session};
+use crate::units::LengthUnit;
+
+/// Classifications from the lowest to the highest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Classification {
+    ArcherThird,
+    ArcherSecond,
+    ArcherFirst,
+    BowmanThird,
+    BowmanSecond,
+    BowmanFirst,
+    MasterBowman,
+    GrandMasterBowman,
+}
+
+impl Classification {
+    /// Every classification, lowest first
+    pub const ALL: [Classification; 8] = [
+        Classification::ArcherThird,
+        Classification::ArcherSecond,
+        Classification::ArcherFirst,
+        Classification::BowmanThird,
+        Classification::BowmanSecond,
+        Classification::BowmanFirst,
+        Classification::MasterBowman,
+        Classification::GrandMasterBowman,
+    ];
+
+    /// Returns the classification above this one, if any
+    pub fn next(&self) -> Option<Classification> {
+        Classification::ALL.get(*self as usize + 1).copied()
+    }
+}
+
+/// The categories an archer is classified in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Category {
+    pub style: BowStyle,
+    pub gender: GenderCategory,
+    pub age: AgeCategory,
+}
+
+impl Category {
+    /// Returns the categories of the archer and bow of a Session, or None unless both the bow
+    /// and the archer's gender are known
+    ///
+    /// An unknown age is taken as adult, and a date of birth is read against the year of the
+    /// session
+    pub fn for_session(session: &Session) -> Option<Category> {
+        let archer = session.archer.as_ref()?;
+        let age = match &archer.age {
+            Some(Age::Category(age)) => *age,
+            Some(Age::DateOfBirth(text)) => {
+                match (Date::parse_legacy(text), session.date) {
+                    (Ok(birth), Some(date)) => age_category(birth.year(), date.date.year()),
+                    _ => AgeCategory::Adult,
+                }
+            },
+            None => AgeCategory::Adult,
+        };
+
+        Some(Category {
+            style: session.bow.as_ref()?.style,
+            gender: archer.gender?,
+            age,
+        })
+    }
+}
+
+/// Returns the age category of an archer born in the given year, for a season in another, by
+/// the age they reach that year
+pub fn age_category(birth_year: u16, season_year: u16) -> AgeCategory {
+    match season_year.saturating_sub(birth_year) {
+        0..=11 => AgeCategory::Under12,
+        12..=13 => AgeCategory::Under14,
+        14 => AgeCategory::Under15,
+        15 => AgeCategory::Under16,
+        16..=17 => AgeCategory::Under18,
+        18..=20 => AgeCategory::Under21,
+        21..=49 => AgeCategory::Adult,
+        50..=59 => AgeCategory::Over50,
+        60..=69 => AgeCategory::Over60,
+        _ => AgeCategory::Over70,
+    }
+}
+
+/// Age steps from adult by age category in the order of AgeCategory
+const AGE_STEPS: [u8; 10] = [5, 4, 3, 2, 1, 0, 0, 1, 2, 3];
+
+/// The handicaps of one bow style in a Scheme
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StyleSteps {
+    /// Handicap for a Master Bowman, adult male
+    pub datum: f64,
+    /// Handicap points between one classification and the next
+    pub class_step: f64,
+    /// Handicap points for each age step from adult
+    pub age_step: f64,
+    pub female_step: f64,
+}
+
+/// Handicaps an archer must reach for each classification
+///
+/// As in the Archery GB 2023 scheme, each bow style has a datum handicap for an adult male
+/// Master Bowman, with every other classification a fixed number of handicap points away and
+/// steps added for age and gender. A handicap may fall between whole handicaps, and the score
+/// it needs is rounded up as the tables round it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scheme {
+    /// By bow style in the order of BowStyle
+    pub styles: [StyleSteps; 5],
+}
+
+/// Internal method for the steps of a bow style
+const fn steps(datum: f64, class_step: f64, age_step: f64, female_step: f64) -> StyleSteps {
+    StyleSteps { datum, class_step, age_step, female_step }
+}
+
+impl Scheme {
+    /// The Archery GB 2023 outdoor scheme
+    pub const AGB_OUTDOOR: Scheme = Scheme {
+        styles: [
+            steps(30.0, 7.0, 6.5, 7.0),
+            steps(15.0, 6.0, 6.0, 5.0),
+            steps(47.0, 6.0, 5.5, 5.5),
+            steps(65.0, 6.0, 6.0, 8.0),
+            steps(60.0, 6.0, 6.0, 7.0),
+        ],
+    };
+
+    /// The Archery GB 2023 indoor scheme
+    pub const AGB_INDOOR: Scheme = Scheme {
+        styles: [
+            steps(25.0, 7.0, 6.5, 7.0),
+            steps(11.0, 6.0, 6.0, 5.0),
+            steps(42.0, 6.0, 5.5, 5.5),
+            steps(60.0, 6.0, 6.0, 8.0),
+            steps(55.0, 6.0, 6.0, 7.0),
+        ],
+    };
+
+    /// Returns the scheme a round is classified under, or None for field and 3D rounds
+    pub fn for_round(round: &RoundDefinition) -> Option<Scheme> {
+        if round.distances.iter().any(|d| matches!(d.face, FaceKind::WaField | FaceKind::ThreeD)) {
+            None
+        } else if round.indoor {
+            Some(Scheme::AGB_INDOOR)
+        } else {
+            Some(Scheme::AGB_OUTDOOR)
+        }
+    }
+
+    /// Returns the worst handicap that earns a classification in the given categories
+    pub fn threshold(&self, classification: Classification, category: &Category) -> f64 {
+        let steps = &self.styles[category.style as usize];
+        let gender = match category.gender {
+            GenderCategory::Female => steps.female_step,
+            GenderCategory::Male | GenderCategory::Open => 0.0,
+        };
+        let below = (Classification::MasterBowman as i32) - classification as i32;
+
+        steps.datum
+            + gender
+            + AGE_STEPS[category.age as usize] as f64 * steps.age_step
+            + below as f64 * steps.class_step
+    }
+
+    /// Returns the best classification a handicap earns, if any
+    pub fn classify(&self, handicap: f64, category: &Category) -> Option<Classification> {
+        Classification::ALL.iter()
+            .rev()
+            .find(|c| handicap <= self.threshold(**c, category))
+            .copied()
+    }
+
+    /// Returns the score a classification needs on a round in the given categories, or None if
+    /// the round has no handicaps
+    pub fn score_needed(&self, round: &RoundDefinition, classification: Classification, category: &Category) -> Option<u32> {
+        let handicap = self.threshold(classification, category).max(handicap::MIN_HANDICAP as f64);
+        Some(handicap::score_for_handicap(round, handicap)?.ceil() as u32)
+    }
+}
+
+/// Distances in metres a category's longest distance may be, longest first
+const DISTANCES: [u32; 8] = [90, 70, 60, 50, 40, 30, 20, 15];
+
+/// Index in DISTANCES of the longest distance each age category shoots, male then female, in
+/// the order of AgeCategory
+const LONGEST: [(usize, usize); 10] = [
+    (5, 6),
+    (4, 5),
+    (3, 4),
+    (2, 3),
+    (1, 2),
+    (0, 1),
+    (0, 1),
+    (1, 2),
+    (1, 2),
+    (1, 2),
+];
+
+/// Returns the shortest distance in metres a round must reach to earn a classification, or None
+/// for the Archer classifications which any round may earn
+///
+/// Bowman First and above need the category's longest distance. Males of Under 16 and older
+/// may shoot one distance shorter for each classification below, and everyone else may do so
+/// from Bowman Third
+fn min_distance(classification: Classification, category: &Category) -> Option<u32> {
+    if classification < Classification::BowmanThird {
+        return None;
+    }
+    let (male, female) = LONGEST[category.age as usize];
+    let junior = (category.age as usize) < AgeCategory::Under16 as usize;
+    let (longest, grace) = match category.gender {
+        GenderCategory::Male | GenderCategory::Open if !junior => (male, 0),
+        GenderCategory::Male | GenderCategory::Open => (male, 1),
+        GenderCategory::Female => (female, 1),
+    };
+    let below = (Classification::BowmanFirst as usize).saturating_sub(classification as usize);
+
+    Some(DISTANCES[(longest + below.saturating_sub(grace)).min(DISTANCES.len() - 1)])
+}
+
+/// Returns whether Master Bowman and above may be earned on a round with a bow style
+///
+/// The 50m WA 720s are for compound and barebow alone
+fn is_prestige(round: &RoundDefinition, style: BowStyle) -> bool {
+    match round.name {
+        "York" | "Hereford" | "WA 1440 (90m)" | "WA 1440 (70m)" | "WA 720 (70m)" | "WA 720 (60m)" => true,
+        "WA 720 (50m)" => style == BowStyle::Compound,
+        "WA 720 (50m barebow)" => style == BowStyle::Barebow,
+        _ => false,
+    }
+}
+
+/// Returns whether a round is one on which a classification may be earned in the given
+/// categories
+///
+/// Outdoors, Bowman Third and above need a round reaching distances suited to the archer's age
+/// and gender, and Master Bowman and above need a prestige round as well. A distance in yards
+/// counts as the same number of metres, as the scheme pairs them, and a WA 720 counts as the
+/// longest distance of the categories it is shot by. Indoor rounds may earn any classification
+pub fn allows(round: &RoundDefinition, classification: Classification, category: &Category) -> bool {
+    if round.indoor {
+        return true;
+    }
+    if classification >= Classification::MasterBowman && !is_prestige(round, category.style) {
+        return false;
+    }
+    let Some(min) = min_distance(classification, category) else {
+        return true;
+    };
+
+    let longest = match round.name {
+        "WA 720 (70m)" => 90.0,
+        "WA 720 (60m)" => 70.0,
+        "WA 720 (50m)" | "WA 720 (50m barebow)" if is_prestige(round, category.style) => 90.0,
+        _ => round.distances.iter()
+            .map(|d| match d.max_distance.unit {
+                LengthUnit::Yards => d.max_distance.value as f64,
+                _ => d.max_distance.to(LengthUnit::Metres),
+            })
+            .fold(0.0, f64::max),
+    };
+    longest >= min as f64
+}
+
+/// The classification a score earns, and what the next one would need
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Assessment {
+    pub handicap: i32,
+    pub achieved: Option<Classification>,
+    /// The classification above the one achieved and the score it needs on the same round
+    pub next: Option<(Classification, u32)>,
+}
+
+/// Returns the classification a score on a round earns in the given categories under the
+/// scheme for the round, or None if the round has no classifications
+///
+/// Only classifications the round `allows` are earned, and the next one is None if the round
+/// does not allow it
+pub fn assess(round: &RoundDefinition, score: u32, category: &Category) -> Option<Assessment> {
+    let scheme = Scheme::for_round(round)?;
+    let handicap = handicap::handicap_for_score(round, score)?;
+    let mut achieved = None;
+    for c in Classification::ALL.into_iter().filter(|c| allows(round, *c, category)) {
+        if scheme.score_needed(round, c, category)? <= score {
+            achieved = Some(c);
+        }
+    }
+
+    let next = match achieved {
+        Some(c) => c.next(),
+        None => Some(Classification::ArcherThird),
+    };
+    let next = match next.filter(|c| allows(round, *c, category)) {
+        Some(c) => {
+            let score = scheme.score_needed(round, c, category)?;
+            // a score the round cannot hold is no use
+            Some((c, score)).filter(|(_, score)| *score <= round.max_score())
+        },
+        None => None,
+    };
+
+    Some(Assessment {
+        handicap,
+        achieved,
+        next,
+    })
+}
+
+/// Returns the classification a scored Round earns, looking its definition up by name
+pub fn assess_round(round: &Round, category: &Category) -> Option<Assessment> {
+    assess(rounds::find(&round.name)?, u32::try_from(round.total()).ok()?, category)
+}
+
+/// How many qualifying scores earn a classification within a season
+pub const QUALIFYING_SCORES: usize = 3;
+
+/// A classification earned over a season
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Claim {
+    pub indoor: bool,
+    /// The calendar year of an outdoor season, or the year an indoor season starts in July
+    pub season: u16,
+    pub category: Category,
+    pub classification: Classification,
+}
+
+/// Returns the season a date falls in
+fn season(date: Date, indoor: bool) -> u16 {
+    if indoor && date.month() < 7 {
+        date.year() - 1
+    } else {
+        date.year()
+    }
+}
+
+/// Returns the classifications earned across many Sessions, one for each season and category
+/// with at least `QUALIFYING_SCORES` rounds at or above it
+///
+/// Only complete rounds of known definitions count, from Sessions with a date and categories,
+/// and each towards the classifications `assess` finds the round allows
+pub fn claims(sessions: &[Session]) -> Vec<Claim> {
+    // achieved classifications by indoor, season and category, in the order first seen
+    let mut achieved: Vec<((bool, u16, Category), Vec<Classification>)> = vec![];
+
+    for session in sessions {
+        let (Some(date), Some(category)) = (session.date, Category::for_session(session)) else {
+            continue;
+        };
+
+        for round in &session.rounds {
+            let Some(definition) = rounds::find(&round.name) else {
+                continue;
+            };
+            if !definition.validate(round).is_empty() {
+                continue;
+            }
+
+            let Ok(score) = u32::try_from(round.total()) else {
+                continue;
+            };
+            if let Some(Assessment { achieved: Some(c), .. }) = assess(definition, score, &category) {
+                let key = (definition.indoor, season(date.date, definition.indoor), category);
+                match achieved.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, results)) => results.push(c),
+                    None => achieved.push((key, vec![c])),
+                }
+            }
+        }
+    }
+
+    achieved.into_iter()
+        .filter_map(|((indoor, season, category), mut results)| {
+            results.sort_unstable_by(|a, b| b.cmp(a));
+            results.get(QUALIFYING_SCORES - 1).map(|classification| Claim {
+                indoor,
+                season,
+                category,
+                classification: *classification,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archer::Archer;
+    use crate::bow::Bow;
+    use crate::date::SessionDate;
+    use crate::rounds::tests::shot_portsmouth;
+    use crate::session::{End, ScoreValue, ValueScore};
+
+    fn category() -> Category {
+        Category {
+            style: BowStyle::Recurve,
+            gender: GenderCategory::Male,
+            age: AgeCategory::Adult,
+        }
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let scheme = Scheme::AGB_OUTDOOR;
+        let cases = [
+            (BowStyle::Recurve, GenderCategory::Male, AgeCategory::Adult, Classification::MasterBowman, 30.0),
+            (BowStyle::Recurve, GenderCategory::Male, AgeCategory::Adult, Classification::GrandMasterBowman, 23.0),
+            (BowStyle::Recurve, GenderCategory::Male, AgeCategory::Adult, Classification::ArcherThird, 72.0),
+            (BowStyle::Recurve, GenderCategory::Female, AgeCategory::Adult, Classification::MasterBowman, 37.0),
+            (BowStyle::Recurve, GenderCategory::Open, AgeCategory::Under21, Classification::MasterBowman, 30.0),
+            (BowStyle::Recurve, GenderCategory::Male, AgeCategory::Under16, Classification::BowmanFirst, 50.0),
+            (BowStyle::Compound, GenderCategory::Male, AgeCategory::Adult, Classification::GrandMasterBowman, 9.0),
+            (BowStyle::Compound, GenderCategory::Female, AgeCategory::Under18, Classification::MasterBowman, 26.0),
+            (BowStyle::Barebow, GenderCategory::Male, AgeCategory::Over50, Classification::MasterBowman, 52.5),
+            (BowStyle::Barebow, GenderCategory::Female, AgeCategory::Under12, Classification::MasterBowman, 80.0),
+            (BowStyle::Longbow, GenderCategory::Female, AgeCategory::Adult, Classification::BowmanSecond, 85.0),
+            (BowStyle::Longbow, GenderCategory::Male, AgeCategory::Over70, Classification::MasterBowman, 83.0),
+            (BowStyle::Traditional, GenderCategory::Male, AgeCategory::Adult, Classification::MasterBowman, 60.0),
+        ];
+        for (style, gender, age, classification, handicap) in cases {
+            let category = Category { style, gender, age };
+            assert_eq!(handicap, scheme.threshold(classification, &category), "{:?} {:?}", classification, category);
+        }
+
+        assert_eq!(25.0, Scheme::AGB_INDOOR.threshold(Classification::MasterBowman, &category()));
+
+        assert_eq!(Some(Classification::GrandMasterBowman), scheme.classify(12.0, &category()));
+        assert_eq!(Some(Classification::BowmanFirst), scheme.classify(37.0, &category()));
+        assert_eq!(Some(Classification::BowmanSecond), scheme.classify(37.5, &category()));
+        assert_eq!(None, scheme.classify(73.0, &category()));
+    }
+
+    #[test]
+    fn test_score_needed() {
+        let york = rounds::find("York").unwrap();
+        let scheme = Scheme::AGB_OUTDOOR;
+        assert_eq!(
+            handicap::table_score(york, 30),
+            scheme.score_needed(york, Classification::MasterBowman, &category())
+        );
+
+        // a handicap between whole handicaps needs a score between theirs
+        let category = Category {
+            style: BowStyle::Barebow,
+            age: AgeCategory::Over50,
+            ..category()
+        };
+        let needed = scheme.score_needed(york, Classification::MasterBowman, &category).unwrap();
+        assert!(needed <= handicap::table_score(york, 52).unwrap());
+        assert!(needed >= handicap::table_score(york, 53).unwrap());
+    }
+
+    #[test]
+    fn test_allows() {
+        let york = rounds::find("York").unwrap();
+        let hereford = rounds::find("Hereford").unwrap();
+        let national = rounds::find("National").unwrap();
+        let female = Category {
+            gender: GenderCategory::Female,
+            ..category()
+        };
+        let junior = Category {
+            age: AgeCategory::Under14,
+            ..female
+        };
+
+        assert!(allows(york, Classification::GrandMasterBowman, &category()));
+        assert!(!allows(hereford, Classification::MasterBowman, &category()));
+        assert!(!allows(hereford, Classification::BowmanFirst, &category()));
+        assert!(allows(hereford, Classification::BowmanSecond, &category()));
+        assert!(allows(hereford, Classification::GrandMasterBowman, &female));
+
+        // adult males step down a distance for each Bowman classification below the first
+        assert!(!allows(national, Classification::BowmanSecond, &category()));
+        assert!(allows(national, Classification::BowmanThird, &category()));
+        assert!(allows(national, Classification::ArcherFirst, &category()));
+        assert!(allows(national, Classification::BowmanFirst, &junior));
+        assert!(!allows(national, Classification::MasterBowman, &junior));
+
+        // everyone else only steps down from Bowman Third
+        let warwick = rounds::find("Warwick").unwrap();
+        assert!(!allows(warwick, Classification::BowmanSecond, &female));
+        assert!(allows(warwick, Classification::BowmanThird, &female));
+        assert!(allows(warwick, Classification::BowmanSecond, &Category { age: AgeCategory::Under16, ..female }));
+
+        assert!(allows(rounds::find("WA 720 (70m)").unwrap(), Classification::MasterBowman, &category()));
+        assert!(!allows(rounds::find("WA 720 (60m)").unwrap(), Classification::BowmanFirst, &category()));
+
+        let compound = Category {
+            style: BowStyle::Compound,
+            ..category()
+        };
+        let compound_720 = rounds::find("WA 720 (50m)").unwrap();
+        assert!(allows(compound_720, Classification::GrandMasterBowman, &compound));
+        assert!(!allows(compound_720, Classification::BowmanThird, &category()));
+        assert!(allows(compound_720, Classification::ArcherFirst, &category()));
+
+        assert!(allows(rounds::find("Portsmouth").unwrap(), Classification::GrandMasterBowman, &category()));
+    }
+
+    #[test]
+    fn test_age_category() {
+        assert_eq!(AgeCategory::Under12, age_category(2014, 2024));
+        assert_eq!(AgeCategory::Under18, age_category(2007, 2024));
+        assert_eq!(AgeCategory::Adult, age_category(1990, 2024));
+        assert_eq!(AgeCategory::Over50, age_category(1974, 2024));
+    }
+
+    #[test]
+    fn test_assess() {
+        let york = rounds::find("York").unwrap();
+        let needed = handicap::table_score(york, 37).unwrap();
+
+        let res = assess(york, needed, &category()).unwrap();
+        assert_eq!(Some(Classification::BowmanFirst), res.achieved);
+        assert_eq!(
+            Some((Classification::MasterBowman, handicap::table_score(york, 30).unwrap())),
+            res.next
+        );
+
+        let res = assess(york, needed - 1, &category()).unwrap();
+        assert_eq!(Some(Classification::BowmanSecond), res.achieved);
+        assert_eq!(Some((Classification::BowmanFirst, needed)), res.next);
+
+        let res = assess(york, 0, &category()).unwrap();
+        assert_eq!(None, res.achieved);
+        assert_eq!(Classification::ArcherThird, res.next.unwrap().0);
+
+        // a perfect National is only a Bowman Third for an adult male
+        let national = rounds::find("National").unwrap();
+        let res = assess(national, national.max_score(), &category()).unwrap();
+        assert_eq!(Some(Classification::BowmanThird), res.achieved);
+        assert_eq!(None, res.next);
+
+        assert_eq!(None, assess(rounds::find("WA Field").unwrap(), 300, &category()));
+    }
+
+    /// Returns a Session on the given day with a Portsmouth of 60 arrows of the given value
+    fn portsmouth_session(year: u16, month: u8, value: u8) -> Session {
+        Session {
+            date: Some(SessionDate::new(Date::new(year, month, 1).unwrap())),
+            bow: Some(Bow::new(BowStyle::Recurve)),
+            archer: Some(Archer {
+                gender: Some(GenderCategory::Male),
+                ..Archer::new("Robin".to_string())
+            }),
+            rounds: vec![shot_portsmouth(ScoreValue::Zone(value))],
+            ..Session::new("Home".to_string())
+        }
+    }
+
+    #[test]
+    fn test_claims() {
+        let portsmouth = rounds::find("Portsmouth").unwrap();
+        let nine = assess(portsmouth, 540, &category()).unwrap().achieved.unwrap();
+        let eight = assess(portsmouth, 480, &category()).unwrap().achieved.unwrap();
+        assert!(nine > eight);
+
+        // two qualifying scores are not enough
+        let mut sessions = vec![portsmouth_session(2023, 10, 9), portsmouth_session(2023, 11, 9)];
+        assert_eq!(Vec::<Claim>::new(), claims(&sessions));
+
+        // the third only needs to reach the lower classification
+        sessions.push(portsmouth_session(2024, 2, 8));
+        assert_eq!(
+            vec![Claim {
+                indoor: true,
+                season: 2023,
+                category: category(),
+                classification: eight,
+            }],
+            claims(&sessions)
+        );
+
+        // a score in the next indoor season does not count towards this one
+        sessions[2] = portsmouth_session(2024, 8, 9);
+        assert_eq!(Vec::<Claim>::new(), claims(&sessions));
+
+        // incomplete rounds do not count
+        sessions[2] = portsmouth_session(2023, 12, 9);
+        sessions[2].rounds[0].targets[0].ends.pop();
+        assert_eq!(Vec::<Claim>::new(), claims(&sessions));
+
+        // scores on rounds too short for a classification count towards the best they allow
+        let national = rounds::find("National").unwrap();
+        for session in &mut sessions {
+            let mut round = national.new_round();
+            for (target, distance) in round.targets.iter_mut().zip(national.distances) {
+                for _ in 0..distance.ends() {
+                    target.ends.push(End::Scored(vec![ValueScore { value: ScoreValue::Zone(9) }; 6]));
+                }
+            }
+            session.rounds = vec![round];
+        }
+        assert_eq!(Classification::BowmanThird, claims(&sessions)[0].classification);
+    }
+}
@@ -0,0 +1,507 @@
+use std::fmt;
+use crate::buffer::{Buffer, BufferError, Reader};
+use crate::Serialisable;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateError {
+    /// The text is not a date in any format this library understands
+    Unrecognised,
+    /// A part of the date or time is outside its range, such as a 13th month
+    OutOfRange,
+}
+
+/// A calendar day in the proleptic Gregorian calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+/// Internal method for the number of days in the given month
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl Date {
+    pub fn new(year: u16, month: u8, day: u8) -> Result<Date, DateError> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return Err(DateError::OutOfRange);
+        }
+
+        Ok(Date {
+            year,
+            month,
+            day,
+        })
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Returns the number of days since 1970-01-01, negative before it
+    pub fn days_since_epoch(&self) -> i64 {
+        // from Howard Hinnant's days_from_civil
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        era * 146097 + day_of_era - 719468
+    }
+
+    /// Returns the Date the given number of days after 1970-01-01
+    pub fn from_days_since_epoch(days: i64) -> Result<Date, DateError> {
+        // from Howard Hinnant's civil_from_days
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let day_of_era = z - era * 146097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        let year = u16::try_from(year).map_err(|_| DateError::OutOfRange)?;
+        Date::new(year, month as u8, day as u8)
+    }
+
+    /// Returns the day of the week, 0 for Monday up to 6 for Sunday
+    pub fn weekday(&self) -> u8 {
+        // 1970-01-01 was a Thursday
+        (self.days_since_epoch() + 3).rem_euclid(7) as u8
+    }
+
+    /// Returns the ISO 8601 week-numbering year and week, where weeks start on a Monday and week 1
+    /// holds the year's first Thursday
+    pub fn iso_week(&self) -> (u16, u8) {
+        let thursday = self.days_since_epoch() - self.weekday() as i64 + 3;
+        let year = Date::from_days_since_epoch(thursday).map_or(self.year, |d| d.year);
+        let first = Date { year, month: 1, day: 1 }.days_since_epoch();
+
+        (year, ((thursday - first) / 7 + 1) as u8)
+    }
+
+    /// Parses a YYYY-MM-DD date
+    pub fn parse(text: &str) -> Result<Date, DateError> {
+        let parts: Vec<&str> = text.split('-').collect();
+        match parts.as_slice() {
+            [y, m, d] if y.len() == 4 && m.len() == 2 && d.len() == 2 => {
+                Date::new(number(y)?, number(m)?, number(d)?)
+            },
+            _ => Err(DateError::Unrecognised),
+        }
+    }
+
+    /// Parses a date written by hand or by an older app
+    ///
+    /// Understands YYYY-MM-DD, numeric dates with `/`, `-` or `.` separators, and dates with
+    /// English month names such as "4 Dec 2023" or "December 4th, 2023". Numeric dates with the
+    /// year last are read day first, as in "4/12/2023", unless only month first makes sense.
+    pub fn parse_legacy(text: &str) -> Result<Date, DateError> {
+        let text = text.trim();
+        if let Ok(date) = Date::parse(text) {
+            return Ok(date);
+        }
+
+        let parts: Vec<&str> = text
+            .split(|c: char| c == '/' || c == '-' || c == '.' || c == ',' || c.is_whitespace())
+            .filter(|p| !p.is_empty())
+            .collect();
+        if parts.len() != 3 {
+            return Err(DateError::Unrecognised);
+        }
+
+        if let Some(i) = parts.iter().position(|p| month_from_name(p).is_some()) {
+            let month = month_from_name(parts[i]).unwrap();
+            let rest: Vec<&str> = parts.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, p)| *p).collect();
+            let (day, year) = if rest[0].len() == 4 { (rest[1], rest[0]) } else { (rest[0], rest[1]) };
+
+            return Date::new(year_number(year)?, month, number(strip_ordinal(day))?);
+        }
+
+        if parts[0].len() == 4 {
+            return Date::new(number(parts[0])?, number(parts[1])?, number(parts[2])?);
+        }
+
+        let year = year_number(parts[2])?;
+        let first = number(parts[0])?;
+        let second = number(parts[1])?;
+        if second > 12 && first <= 12 {
+            Date::new(year, first, second)
+        } else {
+            Date::new(year, second, first)
+        }
+    }
+}
+
+/// Internal method for parsing a part of a date made only of digits
+fn number<T: std::str::FromStr>(text: &str) -> Result<T, DateError> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(DateError::Unrecognised);
+    }
+    text.parse().map_err(|_| DateError::OutOfRange)
+}
+
+/// Internal method for parsing a four digit year, or a two digit one in the 2000s
+fn year_number(text: &str) -> Result<u16, DateError> {
+    match text.len() {
+        4 => number(text),
+        2 => Ok(2000 + number::<u16>(text)?),
+        _ => Err(DateError::Unrecognised),
+    }
+}
+
+/// Internal method for removing "st", "nd", "rd" or "th" from a day
+fn strip_ordinal(text: &str) -> &str {
+    ["st", "nd", "rd", "th"].iter()
+        .find_map(|suffix| text.strip_suffix(suffix))
+        .unwrap_or(text)
+}
+
+/// Internal method for reading an English month name, full or shortened to three letters
+fn month_from_name(text: &str) -> Option<u8> {
+    const MONTHS: [&str; 12] = [
+        "january", "february", "march", "april", "may", "june",
+        "july", "august", "september", "october", "november", "december",
+    ];
+
+    let text = text.to_ascii_lowercase();
+    if text.len() < 3 {
+        return None;
+    }
+    MONTHS.iter()
+        .position(|m| m.starts_with(&text) || (text.len() == 4 && text == "sept" && *m == "september"))
+        .map(|i| i as u8 + 1)
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Serialisable for Date {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        res.append_u16(self.year);
+        res.append_u8(self.month);
+        res.append_u8(self.day);
+        Ok(())
+    }
+
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
+        let offset = data.position();
+        let (year, month, day) = (data.pop_u16()?, data.pop_u8()?, data.pop_u8()?);
+        Date::new(year, month, day).map_err(|_| BufferError::InvalidDateTime { offset })
+    }
+}
+
+/// A time of day, to the second
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl Time {
+    pub fn new(hour: u8, minute: u8, second: u8) -> Result<Time, DateError> {
+        if hour > 23 || minute > 59 || second > 59 {
+            return Err(DateError::OutOfRange);
+        }
+
+        Ok(Time {
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Parses an HH:MM or HH:MM:SS time
+    pub fn parse(text: &str) -> Result<Time, DateError> {
+        let parts: Vec<&str> = text.split(':').collect();
+        match parts.as_slice() {
+            [h, m] if h.len() == 2 && m.len() == 2 => Time::new(number(h)?, number(m)?, 0),
+            [h, m, s] if h.len() == 2 && m.len() == 2 && s.len() == 2 => {
+                Time::new(number(h)?, number(m)?, number(s)?)
+            },
+            _ => Err(DateError::Unrecognised),
+        }
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+impl Serialisable for Time {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        res.append_u8(self.hour);
+        res.append_u8(self.minute);
+        res.append_u8(self.second);
+        Ok(())
+    }
+
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
+        let offset = data.position();
+        let (hour, minute, second) = (data.pop_u8()?, data.pop_u8()?, data.pop_u8()?);
+        Time::new(hour, minute, second).map_err(|_| BufferError::InvalidDateTime { offset })
+    }
+}
+
+/// The difference between local time and UTC, in minutes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UtcOffset {
+    minutes: i16,
+}
+
+impl UtcOffset {
+    pub const UTC: UtcOffset = UtcOffset { minutes: 0 };
+
+    pub fn from_minutes(minutes: i16) -> Result<UtcOffset, DateError> {
+        if minutes.unsigned_abs() > 18 * 60 {
+            return Err(DateError::OutOfRange);
+        }
+
+        Ok(UtcOffset {
+            minutes,
+        })
+    }
+
+    pub fn minutes(&self) -> i16 {
+        self.minutes
+    }
+
+    /// Parses "Z" or a +HH:MM / -HH:MM offset
+    pub fn parse(text: &str) -> Result<UtcOffset, DateError> {
+        if text == "Z" {
+            return Ok(UtcOffset::UTC);
+        }
+
+        let (sign, rest) = match text.split_at_checked(1) {
+            Some(("+", rest)) => (1, rest),
+            Some(("-", rest)) => (-1, rest),
+            _ => return Err(DateError::Unrecognised),
+        };
+        match rest.split(':').collect::<Vec<&str>>().as_slice() {
+            [h, m] if h.len() == 2 && m.len() == 2 => {
+                UtcOffset::from_minutes(sign * (number::<i16>(h)? * 60 + number::<i16>(m)?))
+            },
+            _ => Err(DateError::Unrecognised),
+        }
+    }
+}
+
+impl fmt::Display for UtcOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.minutes == 0 {
+            return write!(f, "Z");
+        }
+
+        let sign = if self.minutes < 0 { '-' } else { '+' };
+        let minutes = self.minutes.abs();
+        write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+    }
+}
+
+impl Serialisable for UtcOffset {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        res.append_i16(self.minutes);
+        Ok(())
+    }
+
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
+        let offset = data.position();
+        UtcOffset::from_minutes(data.pop_i16()?).map_err(|_| BufferError::InvalidDateTime { offset })
+    }
+}
+
+/// When a Session was shot: the day, and the times it started and finished if known
+#[derive(Serialisable, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionDate {
+    pub date: Date,
+    pub start: Option<Time>,
+    pub end: Option<Time>,
+    /// Offset of the start and end times from UTC, if known
+    pub utc_offset: Option<UtcOffset>,
+}
+
+impl SessionDate {
+    /// Returns a SessionDate on the given day with no times recorded
+    pub fn new(date: Date) -> SessionDate {
+        SessionDate {
+            date,
+            start: None,
+            end: None,
+            utc_offset: None,
+        }
+    }
+
+    /// Parses a free-text date from a file written before format 0.5.0
+    ///
+    /// As well as the dates `Date::parse_legacy` understands, an ISO 8601 date and time such as
+    /// 2024-01-02T09:30:00+01:00 is read with the time as the start
+    pub fn parse_legacy(text: &str) -> Result<SessionDate, DateError> {
+        let text = text.trim();
+
+        // only a date before the T makes this a date and time, as month names can hold a T
+        if let Some((Ok(date), time)) = text.split_once('T').map(|(date, time)| (Date::parse(date), time)) {
+            let split = time.find(['Z', '+', '-']).unwrap_or(time.len());
+            let (time, offset) = time.split_at(split);
+
+            return Ok(SessionDate {
+                start: Some(Time::parse(time)?),
+                utc_offset: if offset.is_empty() { None } else { Some(UtcOffset::parse(offset)?) },
+                ..SessionDate::new(date)
+            });
+        }
+
+        Ok(SessionDate::new(Date::parse_legacy(text)?))
+    }
+}
+
+impl fmt::Display for SessionDate {
+    /// Writes the date, followed by the start time and offset if known, in ISO 8601 form
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.date)?;
+        if let Some(start) = self.start {
+            write!(f, "T{}", start)?;
+            if let Some(offset) = self.utc_offset {
+                write!(f, "{}", offset)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: u16, month: u8, day: u8) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_date_range() {
+        assert!(Date::new(2024, 2, 29).is_ok());
+        assert_eq!(Err(DateError::OutOfRange), Date::new(2023, 2, 29));
+        assert_eq!(Err(DateError::OutOfRange), Date::new(1900, 2, 29));
+        assert!(Date::new(2000, 2, 29).is_ok());
+        assert_eq!(Err(DateError::OutOfRange), Date::new(2024, 13, 1));
+        assert_eq!(Err(DateError::OutOfRange), Date::new(2024, 4, 31));
+    }
+
+    #[test]
+    fn test_days_since_epoch() {
+        assert_eq!(0, date(1970, 1, 1).days_since_epoch());
+        assert_eq!(19724, date(2024, 1, 2).days_since_epoch());
+        assert_eq!(-1, date(1969, 12, 31).days_since_epoch());
+
+        for days in [-1000, 0, 19724, 60000] {
+            assert_eq!(days, Date::from_days_since_epoch(days).unwrap().days_since_epoch());
+        }
+    }
+
+    #[test]
+    fn test_iso_week() {
+        // a Tuesday
+        assert_eq!(1, date(2024, 1, 2).weekday());
+        assert_eq!((2024, 1), date(2024, 1, 2).iso_week());
+        // belongs to the last week of the year before
+        assert_eq!((2020, 53), date(2021, 1, 3).iso_week());
+        // belongs to the first week of the year after
+        assert_eq!((2025, 1), date(2024, 12, 30).iso_week());
+    }
+
+    #[test]
+    fn test_parse_legacy() {
+        assert_eq!(Ok(date(2024, 1, 2)), Date::parse_legacy("2024-01-02"));
+        assert_eq!(Ok(date(2023, 12, 4)), Date::parse_legacy("4/12/2023"));
+        assert_eq!(Ok(date(2023, 4, 25)), Date::parse_legacy("4/25/2023"));
+        assert_eq!(Ok(date(2023, 12, 4)), Date::parse_legacy("04.12.23"));
+        assert_eq!(Ok(date(2023, 12, 4)), Date::parse_legacy("2023/12/4"));
+        assert_eq!(Ok(date(2023, 12, 4)), Date::parse_legacy("4 Dec 2023"));
+        assert_eq!(Ok(date(2023, 12, 4)), Date::parse_legacy("December 4th, 2023"));
+        assert_eq!(Ok(date(2023, 9, 21)), Date::parse_legacy("21 Sept 2023"));
+
+        assert_eq!(Err(DateError::Unrecognised), Date::parse_legacy("last Tuesday"));
+        assert_eq!(Err(DateError::Unrecognised), Date::parse_legacy("2024"));
+        assert_eq!(Err(DateError::OutOfRange), Date::parse_legacy("31/02/2024"));
+    }
+
+    #[test]
+    fn test_session_date_parse_legacy() {
+        let parsed = SessionDate::parse_legacy("2024-01-02T09:30:00+01:00").unwrap();
+        assert_eq!(date(2024, 1, 2), parsed.date);
+        assert_eq!(Some(Time::new(9, 30, 0).unwrap()), parsed.start);
+        assert_eq!(Some(UtcOffset::from_minutes(60).unwrap()), parsed.utc_offset);
+        assert_eq!("2024-01-02T09:30:00+01:00", parsed.to_string());
+
+        let parsed = SessionDate::parse_legacy("2024-01-02T09:30Z").unwrap();
+        assert_eq!(Some(UtcOffset::UTC), parsed.utc_offset);
+
+        for text in ["4 OCT 2023", "21 AUGUST 2023", "1 SEPT 2023"] {
+            let parsed = SessionDate::parse_legacy(text).unwrap();
+            assert_eq!(Date::parse_legacy(text).unwrap(), parsed.date);
+            assert_eq!(None, parsed.start);
+        }
+    }
+
+    #[test]
+    fn test_session_date_serialise() {
+        let when = SessionDate {
+            start: Some(Time::new(9, 30, 0).unwrap()),
+            utc_offset: Some(UtcOffset::from_minutes(-300).unwrap()),
+            ..SessionDate::new(date(2024, 1, 2))
+        };
+
+        let data = when.serialise().unwrap();
+        assert_eq!(data, vec![232, 7, 1, 2, 1, 9, 30, 0, 0, 1, 212, 254]);
+        assert_eq!(when, SessionDate::deserialise(&mut data.reader()).unwrap());
+    }
+
+    #[test]
+    fn test_date_deserialise_invalid() {
+        let res = Date::deserialise(&mut Reader::new(&[232, 7, 2, 30]));
+        assert_eq!(Err(BufferError::InvalidDateTime { offset: 0 }), res);
+    }
+
+    #[test]
+    fn test_utc_offset_deserialise_invalid() {
+        let res = UtcOffset::deserialise(&mut Reader::new(&i16::MIN.to_le_bytes()));
+        assert_eq!(Err(BufferError::InvalidDateTime { offset: 0 }), res);
+        assert_eq!(Err(DateError::OutOfRange), UtcOffset::from_minutes(i16::MIN));
+    }
+}
@@ -0,0 +1,286 @@
+use crate::session::ScoreValue;
+use crate::units::{Length, LengthUnit};
+use crate::Serialisable;
+
+/// A scoring zone of a face, bounded by the circle of the given radius around each spot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ring {
+    pub value: u8,
+    /// Whether an arrow in this ring counts as an X, for tie breaks
+    pub x: bool,
+    /// Radius of the outer edge as a fraction of the face size
+    pub radius: f64,
+}
+
+impl Ring {
+    /// Returns what an arrow in this ring scores
+    pub fn score_value(&self) -> ScoreValue {
+        if self.x {
+            ScoreValue::X(self.value)
+        } else {
+            ScoreValue::Zone(self.value)
+        }
+    }
+}
+
+/// Internal method for writing the ring tables compactly
+const fn ring(value: u8, x: bool, radius: f64) -> Ring {
+    Ring {
+        value,
+        x,
+        radius,
+    }
+}
+
+const WA_TEN_ZONE: &[Ring] = &[
+    ring(10, true, 0.025),
+    ring(10, false, 0.05),
+    ring(9, false, 0.10),
+    ring(8, false, 0.15),
+    ring(7, false, 0.20),
+    ring(6, false, 0.25),
+    ring(5, false, 0.30),
+    ring(4, false, 0.35),
+    ring(3, false, 0.40),
+    ring(2, false, 0.45),
+    ring(1, false, 0.50),
+];
+
+const WA_FIVE_ZONE: &[Ring] = &[
+    ring(9, false, 0.10),
+    ring(7, false, 0.20),
+    ring(5, false, 0.30),
+    ring(3, false, 0.40),
+    ring(1, false, 0.50),
+];
+
+const WA_COMPOUND_INNER_TEN: &[Ring] = &[
+    ring(10, true, 0.025),
+    ring(9, false, 0.10),
+    ring(8, false, 0.15),
+    ring(7, false, 0.20),
+    ring(6, false, 0.25),
+    ring(5, false, 0.30),
+    ring(4, false, 0.35),
+    ring(3, false, 0.40),
+    ring(2, false, 0.45),
+    ring(1, false, 0.50),
+];
+
+const WA_TRIPLE_SPOT: &[Ring] = &[
+    ring(10, true, 0.025),
+    ring(10, false, 0.05),
+    ring(9, false, 0.10),
+    ring(8, false, 0.15),
+    ring(7, false, 0.20),
+    ring(6, false, 0.25),
+];
+
+const WORCESTER: &[Ring] = &[
+    ring(5, false, 0.10),
+    ring(4, false, 0.20),
+    ring(3, false, 0.30),
+    ring(2, false, 0.40),
+    ring(1, false, 0.50),
+];
+
+const NFAA_FIVE_SPOT: &[Ring] = &[
+    ring(5, true, 0.025),
+    ring(5, false, 0.05),
+    ring(4, false, 0.10),
+];
+
+const WA_FIELD: &[Ring] = &[
+    ring(6, true, 1.0 / 24.0),
+    ring(6, false, 1.0 / 12.0),
+    ring(5, false, 2.0 / 12.0),
+    ring(4, false, 3.0 / 12.0),
+    ring(3, false, 4.0 / 12.0),
+    ring(2, false, 5.0 / 12.0),
+    ring(1, false, 6.0 / 12.0),
+];
+
+const THREE_D: &[Ring] = &[
+    ring(11, false, 0.125),
+    ring(10, false, 0.25),
+    ring(8, false, 0.50),
+    ring(5, false, 1.0),
+];
+
+/// The design of a target face, independent of its size
+#[derive(Serialisable, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FaceKind {
+    /// The WA face of ten rings scoring 1 to 10, with an inner 10 counting as an X
+    #[oaf(tag = 0)]
+    WaTenZone,
+    /// The WA face scored in the five colours, 9, 7, 5, 3 and 1, as in imperial rounds
+    #[oaf(tag = 1)]
+    WaFiveZone,
+    /// The WA face as shot by compounds, where only the inner 10 scores 10
+    #[oaf(tag = 2)]
+    WaCompoundInnerTen,
+    /// Three spots in a vertical line, each the 6 to 10 rings of a face of the given size
+    #[oaf(tag = 3)]
+    WaTripleSpot,
+    /// A white centre scoring 5 and four black rings scoring 4 to 1
+    #[oaf(tag = 4)]
+    Worcester,
+    /// Five spots, one in the centre and one towards each corner, each an X, 5 and 4 ring
+    #[oaf(tag = 5)]
+    NfaaFiveSpot,
+    /// The WA field face of six rings scoring 1 to 6, with an inner 6 counting as an X
+    #[oaf(tag = 6)]
+    WaField,
+    /// An animal target, approximated as circular 11, 10 and 8 zones within a body scoring 5,
+    /// where the face size is the diameter of the 8 zone
+    #[oaf(tag = 7)]
+    ThreeD,
+}
+
+impl FaceKind {
+    /// Returns the scoring rings of each spot, innermost first
+    pub fn rings(&self) -> &'static [Ring] {
+        match self {
+            FaceKind::WaTenZone => WA_TEN_ZONE,
+            FaceKind::WaFiveZone => WA_FIVE_ZONE,
+            FaceKind::WaCompoundInnerTen => WA_COMPOUND_INNER_TEN,
+            FaceKind::WaTripleSpot => WA_TRIPLE_SPOT,
+            FaceKind::Worcester => WORCESTER,
+            FaceKind::NfaaFiveSpot => NFAA_FIVE_SPOT,
+            FaceKind::WaField => WA_FIELD,
+            FaceKind::ThreeD => THREE_D,
+        }
+    }
+
+    /// Returns the centre of each spot as x and y fractions of the face size from the centre of
+    /// the face, with y pointing up
+    pub fn spots(&self) -> &'static [(f64, f64)] {
+        match self {
+            // centres 22 cm apart on a 40 cm triple spot
+            FaceKind::WaTripleSpot => &[(0.0, 0.55), (0.0, 0.0), (0.0, -0.55)],
+            FaceKind::NfaaFiveSpot => &[(0.0, 0.0), (-0.3, 0.3), (0.3, 0.3), (-0.3, -0.3), (0.3, -0.3)],
+            _ => &[(0.0, 0.0)],
+        }
+    }
+
+    /// Returns the highest value any arrow can score on this face
+    pub fn max_value(&self) -> u8 {
+        self.rings()[0].value
+    }
+
+    /// Returns the lowest value scored in the gold, the centre colour counted as golds on a
+    /// score sheet, or None if the face has no gold
+    pub fn gold(&self) -> Option<u8> {
+        match self {
+            FaceKind::WaTenZone | FaceKind::WaFiveZone | FaceKind::WaCompoundInnerTen | FaceKind::WaTripleSpot => Some(9),
+            // the white centre
+            FaceKind::Worcester | FaceKind::NfaaFiveSpot => Some(5),
+            // the yellow spot of the 5 and 6 rings
+            FaceKind::WaField => Some(5),
+            FaceKind::ThreeD => None,
+        }
+    }
+}
+
+/// A face of a given design at a given size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Face {
+    pub kind: FaceKind,
+    pub size: Length,
+}
+
+impl Face {
+    pub fn new(kind: FaceKind, size: Length) -> Face {
+        Face {
+            kind,
+            size,
+        }
+    }
+
+    /// Returns the size of the face in millimetres
+    pub fn size_mm(&self) -> f64 {
+        self.size.to(LengthUnit::Millimetres)
+    }
+
+    /// Returns the radius of the given ring in millimetres
+    pub fn radius_mm(&self, ring: &Ring) -> f64 {
+        ring.radius * self.size_mm()
+    }
+
+    /// Returns the centre of each spot in millimetres from the centre of the face
+    pub fn spot_centres_mm(&self) -> Vec<(f64, f64)> {
+        let size = self.size_mm();
+        self.kind.spots().iter().map(|(x, y)| (x * size, y * size)).collect()
+    }
+
+    /// Returns the distance in millimetres from a point, given in millimetres from the centre of
+    /// the face, to the centre of the nearest spot
+    pub fn distance_to_spot_mm(&self, x: f64, y: f64) -> f64 {
+        self.spot_centres_mm().iter()
+            .map(|(sx, sy)| (x - sx).hypot(y - sy))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Returns the ring a point lies in, given in millimetres from the centre of the face, or
+    /// None if it misses every ring
+    pub fn ring_at(&self, x: f64, y: f64) -> Option<Ring> {
+        let distance = self.distance_to_spot_mm(x, y);
+        self.kind.rings().iter().find(|ring| distance <= self.radius_mm(ring)).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rings_ordered() {
+        for kind in [
+            FaceKind::WaTenZone, FaceKind::WaFiveZone, FaceKind::WaCompoundInnerTen, FaceKind::WaTripleSpot,
+            FaceKind::Worcester, FaceKind::NfaaFiveSpot, FaceKind::WaField, FaceKind::ThreeD,
+        ] {
+            let rings = kind.rings();
+            for pair in rings.windows(2) {
+                assert!(pair[0].radius < pair[1].radius, "{kind:?} rings out of order");
+                assert!(pair[0].value >= pair[1].value, "{kind:?} values out of order");
+            }
+        }
+    }
+
+    #[test]
+    fn test_wa_ten_zone() {
+        let face = Face::new(FaceKind::WaTenZone, Length::new(122, LengthUnit::Centimetres));
+
+        assert_eq!(61.0, face.radius_mm(&face.kind.rings()[1]));
+        assert_eq!(610.0, face.radius_mm(&face.kind.rings()[10]));
+
+        let x = face.ring_at(0.0, 30.0).unwrap();
+        assert_eq!((10, true), (x.value, x.x));
+        assert_eq!(9, face.ring_at(100.0, 0.0).unwrap().value);
+        assert_eq!(1, face.ring_at(-400.0, -400.0).unwrap().value);
+        assert_eq!(None, face.ring_at(0.0, 611.0));
+    }
+
+    #[test]
+    fn test_triple_spot() {
+        let face = Face::new(FaceKind::WaTripleSpot, Length::new(40, LengthUnit::Centimetres));
+
+        // the centre of the top spot
+        assert_eq!(10, face.ring_at(0.0, 220.0).unwrap().value);
+        assert_eq!(6, face.ring_at(95.0, -220.0).unwrap().value);
+        // between the spots
+        assert_eq!(None, face.ring_at(0.0, 110.0));
+    }
+
+    #[test]
+    fn test_imperial_and_field() {
+        let face = Face::new(FaceKind::WaFiveZone, Length::new(48, LengthUnit::Inches));
+        assert_eq!(9, face.ring_at(0.0, 100.0).unwrap().value);
+        assert_eq!(7, face.ring_at(0.0, 200.0).unwrap().value);
+
+        let face = Face::new(FaceKind::WaField, Length::new(60, LengthUnit::Centimetres));
+        assert_eq!(6, face.ring_at(0.0, 49.0).unwrap().value);
+        assert_eq!(5, face.ring_at(0.0, 51.0).unwrap().value);
+        assert_eq!(6, face.kind.max_value());
+    }
+}
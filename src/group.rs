@@ -0,0 +1,211 @@
+use crate::session::{End, Session, Target};
+
+/// How many times further than the rest of the group an arrow must land to be a flier, see
+/// `Group::fliers`
+pub const DEFAULT_FLIER_FACTOR: f64 = 2.5;
+
+/// Positions of arrows in millimetres right of and above the centre of the face
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Group {
+    pub points: Vec<(f64, f64)>,
+}
+
+impl Group {
+    pub fn new(points: Vec<(f64, f64)>) -> Group {
+        Group {
+            points,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the mean point of impact, or None for an empty group
+    pub fn centre(&self) -> Option<(f64, f64)> {
+        if self.points.is_empty() {
+            return None;
+        }
+
+        let n = self.points.len() as f64;
+        let (x, y) = self.points.iter().fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+        Some((x / n, y / n))
+    }
+
+    /// Returns the mean distance of the arrows from the mean point of impact
+    pub fn radius(&self) -> Option<f64> {
+        let (cx, cy) = self.centre()?;
+        let total: f64 = self.points.iter().map(|(x, y)| (x - cx).hypot(y - cy)).sum();
+        Some(total / self.points.len() as f64)
+    }
+
+    /// Returns the sample standard deviation along the horizontal and vertical axes, or None
+    /// for fewer than two arrows
+    pub fn std_dev(&self) -> Option<(f64, f64)> {
+        if self.points.len() < 2 {
+            return None;
+        }
+
+        let (cx, cy) = self.centre()?;
+        let n = self.points.len() as f64 - 1.0;
+        let (vx, vy) = self.points.iter()
+            .fold((0.0, 0.0), |(vx, vy), (x, y)| (vx + (x - cx).powi(2), vy + (y - cy).powi(2)));
+        Some(((vx / n).sqrt(), (vy / n).sqrt()))
+    }
+
+    /// Returns the largest distance between any two arrows, or None for an empty group
+    pub fn extreme_spread(&self) -> Option<f64> {
+        if self.points.is_empty() {
+            return None;
+        }
+
+        let mut res: f64 = 0.0;
+        for (i, (x1, y1)) in self.points.iter().enumerate() {
+            for (x2, y2) in &self.points[i + 1..] {
+                res = res.max((x1 - x2).hypot(y1 - y2));
+            }
+        }
+        Some(res)
+    }
+
+    /// Returns the indices of arrows landing further from the centre of the rest of the group
+    /// than `factor` times the rest's radius
+    ///
+    /// Groups of fewer than three arrows have no fliers
+    pub fn fliers(&self, factor: f64) -> Vec<usize> {
+        if self.points.len() < 3 {
+            return vec![];
+        }
+
+        (0..self.points.len())
+            .filter(|&i| {
+                let rest = self.without(&[i]);
+                let (cx, cy) = rest.centre().unwrap();
+                let radius = rest.radius().unwrap();
+                let (x, y) = self.points[i];
+
+                (x - cx).hypot(y - cy) > factor * radius
+            })
+            .collect()
+    }
+
+    /// Returns this group without the arrows at the given indices
+    pub fn without(&self, indices: &[usize]) -> Group {
+        Group::new(
+            self.points.iter()
+                .enumerate()
+                .filter(|(i, _)| !indices.contains(i))
+                .map(|(_, p)| *p)
+                .collect()
+        )
+    }
+}
+
+impl End {
+    /// Returns the positions of the arrows of a Measured or BareShaft end, and an empty group
+    /// for other kinds
+    pub fn group(&self) -> Group {
+        match self {
+            End::Measured(scores) => Group::new(scores.iter().map(|s| s.position_mm()).collect()),
+            End::BareShaft(shafts) => Group::new(shafts.iter().map(|s| s.position_mm()).collect()),
+            _ => Group::default(),
+        }
+    }
+}
+
+impl Target {
+    /// Returns the positions of every measured arrow, leaving out bare shafts
+    pub fn group(&self) -> Group {
+        Group::new(
+            self.ends.iter()
+                .filter(|end| matches!(end, End::Measured(_)))
+                .flat_map(|end| end.group().points)
+                .collect()
+        )
+    }
+
+    /// Returns the positions of every bare shaft
+    pub fn bare_shaft_group(&self) -> Group {
+        Group::new(
+            self.ends.iter()
+                .filter(|end| matches!(end, End::BareShaft(_)))
+                .flat_map(|end| end.group().points)
+                .collect()
+        )
+    }
+}
+
+impl Session {
+    /// Returns the positions of every measured arrow of every Target, which is only meaningful
+    /// when they were all shot at the same face
+    pub fn group(&self) -> Group {
+        Group::new(
+            self.rounds.iter()
+                .flat_map(|round| round.targets.iter())
+                .flat_map(|target| target.group().points)
+                .collect()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::{BareShaft, MeasuredScore, ScoreValue};
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_group_stats() {
+        let group = Group::new(vec![(10.0, 10.0), (30.0, 10.0), (30.0, -10.0), (10.0, -10.0)]);
+
+        assert_eq!(Some((20.0, 0.0)), group.centre());
+        assert!(close(200f64.sqrt(), group.radius().unwrap()));
+        let (sx, sy) = group.std_dev().unwrap();
+        assert!(close((400.0f64 / 3.0).sqrt(), sx));
+        assert!(close((400.0f64 / 3.0).sqrt(), sy));
+        assert!(close(800f64.sqrt(), group.extreme_spread().unwrap()));
+        assert_eq!(Vec::<usize>::new(), group.fliers(DEFAULT_FLIER_FACTOR));
+
+        assert_eq!(None, Group::default().centre());
+        assert_eq!(None, Group::new(vec![(1.0, 1.0)]).std_dev());
+    }
+
+    #[test]
+    fn test_fliers() {
+        let group = Group::new(vec![(0.0, 5.0), (5.0, 0.0), (0.0, -5.0), (-5.0, 0.0), (100.0, 100.0)]);
+
+        assert_eq!(vec![4], group.fliers(DEFAULT_FLIER_FACTOR));
+        assert_eq!(Some((0.0, 0.0)), group.without(&[4]).centre());
+    }
+
+    #[test]
+    fn test_end_group() {
+        let end = End::Measured(vec![
+            MeasuredScore {
+                value: ScoreValue::Zone(9),
+                r: 4000,
+                theta: 9000,
+            },
+            MeasuredScore {
+                value: ScoreValue::Zone(9),
+                r: 4000,
+                theta: 27000,
+            },
+        ]);
+        let (x, y) = end.group().centre().unwrap();
+        assert!(close(0.0, x) && close(0.0, y));
+
+        let end = End::BareShaft(vec![BareShaft { r: 2000, theta: 18000 }]);
+        let (x, y) = end.group().centre().unwrap();
+        assert!(close(-20.0, x) && close(0.0, y));
+
+        assert!(End::Blank(6).group().is_empty());
+    }
+}
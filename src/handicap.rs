@@ -0,0 +1,222 @@
+use crate::face::{Face, FaceKind};
+use crate::rounds::RoundDefinition;
+use crate::session::Round;
+use crate::units::LengthUnit;
+
+/// Constants of the Archery GB 2023 handicap model
+///
+/// Scores worked out from them have not been checked against the published tables, so a table
+/// entry may differ by the rounding the tables use
+const DATUM: f64 = 6.0;
+const STEP: f64 = 3.5;
+const ANG_0: f64 = 5.0e-4;
+const K_D: f64 = 0.00365;
+
+/// Arrow diameters the model assumes, in metres
+pub const ARROW_DIAMETER_OUTDOOR: f64 = 5.5e-3;
+pub const ARROW_DIAMETER_INDOOR: f64 = 9.3e-3;
+
+/// The best and worst handicaps on the scale
+pub const MIN_HANDICAP: i32 = 0;
+pub const MAX_HANDICAP: i32 = 150;
+
+/// Returns the angular spread of arrows in radians for a handicap at a distance in metres
+pub fn sigma_t(handicap: f64, distance: f64) -> f64 {
+    ANG_0 * (1.0 + STEP / 100.0).powf(handicap + DATUM) * (K_D * distance).exp()
+}
+
+/// Returns the spread of arrows on the face in metres for a handicap at a distance in metres
+pub fn sigma_r(handicap: f64, distance: f64) -> f64 {
+    distance * sigma_t(handicap, distance)
+}
+
+/// Returns the expected score of one arrow for a handicap on a face at a distance in metres
+///
+/// Each ring's edge is pushed out by the arrow's radius, as under line-cutter rules
+pub fn arrow_score(handicap: f64, face: &Face, distance: f64, arrow_diameter: f64) -> f64 {
+    let sigma = sigma_r(handicap, distance);
+    let mut res = 0.0;
+    let mut inside = 0.0;
+
+    for ring in face.kind.rings() {
+        let radius = face.radius_mm(ring) / 1000.0 + arrow_diameter / 2.0;
+        let p = 1.0 - (-(radius / sigma).powi(2)).exp();
+        res += ring.value as f64 * (p - inside);
+        inside = p;
+    }
+
+    res
+}
+
+/// Internal method for the expected score of some arrows at some faces and distances
+fn expected(handicap: f64, arrows: &[(u32, Face, f64)], arrow_diameter: f64) -> f64 {
+    arrows.iter()
+        .map(|(n, face, distance)| *n as f64 * arrow_score(handicap, face, *distance, arrow_diameter))
+        .sum()
+}
+
+/// Internal method for the smallest handicap whose expected score the given score reaches,
+/// where handicaps are rounded up so that a score earns a handicap once it meets the table
+fn solve(score: f64, arrows: &[(u32, Face, f64)], arrow_diameter: f64) -> i32 {
+    let (mut lo, mut hi) = (MIN_HANDICAP, MAX_HANDICAP);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if expected(mid as f64, arrows, arrow_diameter).ceil() <= score {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    lo
+}
+
+/// Internal method for the arrows, faces and distances of a round definition, or None if the
+/// course sets a distance or face size
+fn definition_arrows(round: &RoundDefinition) -> Option<Vec<(u32, Face, f64)>> {
+    round.distances.iter()
+        .map(|d| {
+            if !d.is_fixed() {
+                return None;
+            }
+            Some((d.arrows, Face::new(d.face, d.face_size?), d.max_distance.to(LengthUnit::Metres)))
+        })
+        .collect()
+}
+
+/// Internal method for the arrow diameter the model assumes for a round
+fn arrow_diameter(indoor: bool) -> f64 {
+    if indoor {
+        ARROW_DIAMETER_INDOOR
+    } else {
+        ARROW_DIAMETER_OUTDOOR
+    }
+}
+
+/// Returns the expected score for a handicap on a round, unrounded, or None if the course sets
+/// a distance or face size
+pub fn score_for_handicap(round: &RoundDefinition, handicap: f64) -> Option<f64> {
+    Some(expected(handicap, &definition_arrows(round)?, arrow_diameter(round.indoor)))
+}
+
+/// Returns the score needed on a round to earn a whole handicap, as printed in the tables
+///
+/// The score is the model's expected score rounded up, which has not been checked against the
+/// published tables
+pub fn table_score(round: &RoundDefinition, handicap: i32) -> Option<u32> {
+    Some(score_for_handicap(round, handicap as f64)?.ceil() as u32)
+}
+
+/// Returns the handicap a score on a round earns
+pub fn handicap_for_score(round: &RoundDefinition, score: u32) -> Option<i32> {
+    Some(solve(score as f64, &definition_arrows(round)?, arrow_diameter(round.indoor)))
+}
+
+/// Returns the handicap the arrows shot so far in a Round earn, using each Target's distance
+/// and face, or None if no arrows were shot or a Target with arrows has no known face
+///
+/// As for round definitions, 3D faces and faces without a size are not handicapped
+pub fn handicap_for_partial(round: &Round, indoor: bool) -> Option<i32> {
+    let mut arrows = vec![];
+    for target in &round.targets {
+        let n = target.summary().arrows as u32;
+        if n > 0 {
+            let face = target.face_geometry().filter(|face| face.size.value != 0 && face.kind != FaceKind::ThreeD)?;
+            arrows.push((n, face, target.distance.to(LengthUnit::Metres)));
+        }
+    }
+
+    if arrows.is_empty() {
+        return None;
+    }
+    Some(solve(round.total() as f64, &arrows, arrow_diameter(indoor)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rounds;
+    use crate::session::{End, ScoreValue, ValueScore};
+    use crate::units::Length;
+
+    #[test]
+    fn test_sigma_t() {
+        // from the reference implementation of the model
+        assert!((sigma_t(25.46, 100.0) - 0.002125743670979009).abs() < 1e-15);
+        assert!((sigma_r(25.46, 100.0) - 0.2125743670979009).abs() < 1e-13);
+    }
+
+    #[test]
+    fn test_tables_consistent() {
+        for round in rounds::ROUNDS.iter().filter(|r| !r.is_course_set()) {
+            let mut last = round.max_score();
+            for handicap in MIN_HANDICAP..=MAX_HANDICAP {
+                let score = table_score(round, handicap).unwrap();
+                assert!(score <= last, "{} scores rise at handicap {}", round.name, handicap);
+                last = score;
+
+                // a handicap's table score earns that handicap, unless a better one needs the
+                // same score
+                let earned = handicap_for_score(round, score).unwrap();
+                assert!(earned <= handicap);
+                assert_eq!(score, table_score(round, earned).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_table_scores() {
+        // worked out from the model's equations apart from this implementation, with the
+        // expected score rounded up as the 2023 tables round it
+        let cases = [
+            ("York", [1284, 1171, 905, 492, 178]),
+            ("Portsmouth", [600, 593, 555, 472, 311]),
+            ("WA 1440 (90m)", [1400, 1291, 1065, 674, 290]),
+        ];
+        for (name, scores) in cases {
+            let round = rounds::find(name).unwrap();
+            for (handicap, score) in [0, 20, 40, 60, 80].into_iter().zip(scores) {
+                assert_eq!(Some(score), table_score(round, handicap), "{} at handicap {}", name, handicap);
+                assert_eq!(Some(handicap), handicap_for_score(round, score), "{} scoring {}", name, score);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extremes() {
+        let york = rounds::find("York").unwrap();
+        assert_eq!(Some(MIN_HANDICAP), handicap_for_score(york, 1296));
+        assert_eq!(Some(MAX_HANDICAP), handicap_for_score(york, 0));
+        assert!(score_for_handicap(york, -20.0).unwrap() > 1290.0);
+
+        assert_eq!(None, handicap_for_score(rounds::find("WA 3D").unwrap(), 300));
+        assert_eq!(None, handicap_for_score(rounds::find("WA Field").unwrap(), 300));
+    }
+
+    #[test]
+    fn test_partial() {
+        let portsmouth = rounds::find("Portsmouth").unwrap();
+        let mut round = portsmouth.new_round();
+        assert_eq!(None, handicap_for_partial(&round, true));
+
+        // shooting the same average over the whole round earns the same handicap
+        for _ in 0..20 {
+            round.targets[0].ends.push(End::Scored(vec![ValueScore { value: ScoreValue::Zone(9) }; 3]));
+        }
+        let full = handicap_for_score(portsmouth, 540).unwrap();
+        assert_eq!(Some(full), handicap_for_partial(&round, true));
+
+        round.targets[0].ends.truncate(5);
+        let partial = handicap_for_partial(&round, true).unwrap();
+        assert!((partial - full).abs() <= 1);
+
+        round.targets[0].face = None;
+        assert_eq!(None, handicap_for_partial(&round, true));
+
+        let mut round = rounds::find("WA 3D").unwrap().new_round();
+        round.targets[0].ends.push(End::Scored(vec![ValueScore { value: ScoreValue::Zone(10) }; 2]));
+        assert_eq!(None, handicap_for_partial(&round, false));
+
+        round.targets[0].face_size = Length::new(20, LengthUnit::Centimetres);
+        assert_eq!(None, handicap_for_partial(&round, false));
+    }
+}
@@ -1,2 +1,24 @@
+extern crate self as open_archery_format;
+
 pub mod session;
-mod buffer;
\ No newline at end of file
+pub mod version;
+pub mod migrate;
+pub mod checksum;
+pub mod buffer;
+pub mod serialise;
+pub mod bow;
+pub mod archer;
+pub mod date;
+pub mod units;
+pub mod face;
+pub mod scoring;
+pub mod rounds;
+pub mod stats;
+pub mod group;
+pub mod tuning;
+pub mod handicap;
+pub mod classification;
+pub mod volume;
+
+pub use serialise::Serialisable;
+pub use open_archery_format_derive::Serialisable;
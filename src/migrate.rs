@@ -0,0 +1,121 @@
+use crate::date::SessionDate;
+use crate::session::Session;
+use crate::version::Version;
+
+/// One step of the migration chain, upgrading a Session decoded from one format version into
+/// the model of the next
+///
+/// Layout differences between versions are handled by the readers of each type, so a step only
+/// has to fix up data whose meaning changed
+pub struct Migration {
+    pub from: Version,
+    pub to: Version,
+    pub apply: fn(&mut Session),
+}
+
+/// The migration chain, oldest first, where each step's `to` is the next step's `from`
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: Version::V0_1_0,
+        to: Version::V0_2_0,
+        // only the width of lengths changed
+        apply: |_| {},
+    },
+    Migration {
+        from: Version::V0_2_0,
+        to: Version::V0_3_0,
+        // only the checksum footer was added
+        apply: |_| {},
+    },
+    Migration {
+        from: Version::V0_3_0,
+        to: Version::V0_4_0,
+        // only the layout of the body changed
+        apply: |_| {},
+    },
+    Migration {
+        from: Version::V0_4_0,
+        to: Version::V0_5_0,
+        apply: parse_legacy_date,
+    },
+    Migration {
+        from: Version::V0_5_0,
+        to: Version::V0_6_0,
+        // unit strings are read leniently by Target
+        apply: |_| {},
+    },
+    Migration {
+        from: Version::V0_6_0,
+        to: Version::V0_7_0,
+        // value names are read into ScoreValues by ScoreValue itself
+        apply: |_| {},
+    },
+    Migration {
+        from: Version::V0_7_0,
+        to: Version::V0_8_0,
+        // positions are scaled by MeasuredScore and BareShaft themselves
+        apply: |_| {},
+    },
+];
+
+/// Reads the free-text date of an older file as a SessionDate, leaving it in `legacy_date` if it
+/// cannot be interpreted
+fn parse_legacy_date(session: &mut Session) {
+    if let Some(text) = &session.legacy_date {
+        if let Ok(date) = SessionDate::parse_legacy(text) {
+            session.date = Some(date);
+            session.legacy_date = None;
+        }
+    }
+}
+
+/// Applies every step of the migration chain from the given version up to the current one
+pub fn upgrade(session: &mut Session, from: Version) {
+    let mut version = from;
+    for migration in MIGRATIONS {
+        if migration.from == version {
+            (migration.apply)(session);
+            version = migration.to;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_reaches_current() {
+        for from in Version::SUPPORTED {
+            let mut version = *from;
+            for migration in MIGRATIONS {
+                if migration.from == version {
+                    version = migration.to;
+                }
+            }
+
+            assert_eq!(Version::CURRENT, version, "no migration path from {from}");
+        }
+    }
+
+    #[test]
+    fn test_chain_versions_supported() {
+        for migration in MIGRATIONS {
+            assert!(migration.from < migration.to);
+            assert!(migration.from.is_supported());
+            assert!(migration.to.is_supported());
+        }
+    }
+
+    #[test]
+    fn test_parse_legacy_date() {
+        let mut session = Session {
+            legacy_date: Some("4 Dec 2023".to_string()),
+            ..Session::new("St Andrews".to_string())
+        };
+
+        upgrade(&mut session, Version::V0_4_0);
+        assert_eq!("2023-12-04", session.date.unwrap().to_string());
+        assert_eq!(None, session.legacy_date);
+    }
+}
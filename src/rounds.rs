@@ -0,0 +1,686 @@
+use crate::face::FaceKind::{self, NfaaFiveSpot, ThreeD, WaCompoundInnerTen, WaField, WaFiveZone, WaTenZone, WaTripleSpot, Worcester};
+use crate::session::{Round, ScoreValue, Target};
+use crate::units::{Length, LengthUnit};
+
+/// One distance of a standard round, or one face size of a field or 3D round where the course
+/// sets the distances within a range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistanceDefinition {
+    pub min_distance: Length,
+    /// The same as `min_distance` unless the course sets the distance
+    pub max_distance: Length,
+    pub face: FaceKind,
+    /// None where the course sets the face size, as on 3D animals
+    pub face_size: Option<Length>,
+    /// Total arrows shot at this distance
+    pub arrows: u32,
+    pub arrows_per_end: u32,
+    /// How many Targets the arrows are shot at, one per peg on a field or 3D course and
+    /// otherwise one
+    pub targets: u32,
+}
+
+impl DistanceDefinition {
+    pub fn is_fixed(&self) -> bool {
+        self.min_distance == self.max_distance
+    }
+
+    /// Returns the arrows shot at each Target
+    pub fn arrows_per_target(&self) -> u32 {
+        self.arrows / self.targets
+    }
+
+    /// Returns the ends shot at each Target
+    pub fn ends(&self) -> u32 {
+        self.arrows_per_target().div_ceil(self.arrows_per_end)
+    }
+
+    /// Returns whether a Target could be at this distance, ignoring its face
+    pub fn allows_distance(&self, distance: Length) -> bool {
+        let distance = distance.micrometres();
+        distance >= self.min_distance.micrometres() && distance <= self.max_distance.micrometres()
+    }
+
+    /// Returns whether a Target has the face size of this distance, where any size is allowed
+    /// if the course sets it
+    pub fn allows_face_size(&self, face_size: Length) -> bool {
+        self.face_size.is_none_or(|size| size.micrometres() == face_size.micrometres())
+    }
+
+    pub fn dozens(&self) -> f64 {
+        self.arrows as f64 / 12.0
+    }
+
+    /// Returns the highest score possible at this distance
+    pub fn max_score(&self) -> u32 {
+        self.arrows * self.face.max_value() as u32
+    }
+
+    /// Returns whether an arrow can score the given value on this distance's face
+    pub fn is_possible(&self, value: ScoreValue) -> bool {
+        match value {
+            ScoreValue::Miss => true,
+            ScoreValue::Hit => matches!(self.face, FaceKind::WaField | FaceKind::ThreeD),
+            _ => self.face.rings().iter().any(|ring| ring.score_value() == value),
+        }
+    }
+}
+
+/// Something in a Round that does not match its definition, where `target` and `end` are
+/// indices into the Round's targets and the Target's scoring ends
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    WrongTargetCount {
+        expected: usize,
+        actual: usize,
+    },
+    /// A field or 3D round has the wrong number of Targets for a face size, where `distance` is
+    /// an index into the definition's distances
+    WrongTargetsAtDistance {
+        distance: usize,
+        expected: u32,
+        actual: usize,
+    },
+    /// The Target is outside the distances allowed, which are a single distance unless the
+    /// course sets it
+    WrongDistance {
+        target: usize,
+        min: Length,
+        max: Length,
+        actual: Length,
+    },
+    WrongFaceSize {
+        target: usize,
+        expected: Length,
+        actual: Length,
+    },
+    WrongFace {
+        target: usize,
+        expected: FaceKind,
+        actual: FaceKind,
+    },
+    TooManyArrows {
+        target: usize,
+        end: usize,
+        expected: u32,
+        actual: usize,
+    },
+    MissingArrows {
+        target: usize,
+        end: usize,
+        expected: u32,
+        actual: usize,
+    },
+    ImpossibleValue {
+        target: usize,
+        end: usize,
+        arrow: usize,
+        value: ScoreValue,
+    },
+    MissingEnds {
+        target: usize,
+        expected: u32,
+        actual: usize,
+    },
+    ExtraEnds {
+        target: usize,
+        expected: u32,
+        actual: usize,
+    },
+}
+
+/// A standard round: its name, and the distances shot in order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundDefinition {
+    pub name: &'static str,
+    /// Whether the round is shot indoors, where handicaps assume thicker arrows
+    pub indoor: bool,
+    pub distances: &'static [DistanceDefinition],
+}
+
+impl RoundDefinition {
+    pub fn arrows(&self) -> u32 {
+        self.distances.iter().map(|d| d.arrows).sum()
+    }
+
+    pub fn max_score(&self) -> u32 {
+        self.distances.iter().map(|d| d.max_score()).sum()
+    }
+
+    /// Returns the number of Targets a Round of this definition is recorded as
+    pub fn targets(&self) -> usize {
+        self.distances.iter().map(|d| d.targets as usize).sum()
+    }
+
+    /// Returns whether the course sets the distances, so Targets are matched to distances by
+    /// their face size and distance rather than in order
+    pub fn is_course_set(&self) -> bool {
+        self.distances.iter().any(|d| !d.is_fixed() || d.targets != 1)
+    }
+
+    /// Returns the index of the distance a Target of a Round is shot at
+    ///
+    /// Standard rounds are shot in order, and a Target of a field or 3D round belongs to the
+    /// distance it fits best, by face size and then by distance
+    fn distance_of(&self, index: usize, target: &Target) -> Option<usize> {
+        if !self.is_course_set() {
+            return (index < self.distances.len()).then_some(index);
+        }
+
+        let find = |fits: &dyn Fn(&DistanceDefinition) -> bool| self.distances.iter().position(fits);
+        find(&|d| d.allows_face_size(target.face_size) && d.allows_distance(target.distance))
+            .or_else(|| find(&|d| d.allows_face_size(target.face_size)))
+            .or_else(|| find(&|d| d.allows_distance(target.distance)))
+            .or(Some(0))
+    }
+
+    /// Returns every way the given Round differs from this definition, where only Scored and
+    /// Measured ends count as ends
+    pub fn validate(&self, round: &Round) -> Vec<Problem> {
+        let mut res = vec![];
+
+        if round.targets.len() != self.targets() {
+            res.push(Problem::WrongTargetCount {
+                expected: self.targets(),
+                actual: round.targets.len(),
+            });
+        }
+
+        let mut counts = vec![0; self.distances.len()];
+        for (i, target) in round.targets.iter().enumerate() {
+            let Some(index) = self.distance_of(i, target) else {
+                continue;
+            };
+            counts[index] += 1;
+
+            let d = &self.distances[index];
+            if !d.allows_distance(target.distance) {
+                res.push(Problem::WrongDistance {
+                    target: i,
+                    min: d.min_distance,
+                    max: d.max_distance,
+                    actual: target.distance,
+                });
+            }
+
+            if let Some(face_size) = d.face_size {
+                if !d.allows_face_size(target.face_size) {
+                    res.push(Problem::WrongFaceSize {
+                        target: i,
+                        expected: face_size,
+                        actual: target.face_size,
+                    });
+                }
+            }
+
+            if let Some(face) = target.face {
+                if face != d.face {
+                    res.push(Problem::WrongFace {
+                        target: i,
+                        expected: d.face,
+                        actual: face,
+                    });
+                }
+            }
+
+            let ends: Vec<Vec<ScoreValue>> = target.ends.iter()
+                .map(|end| end.score_values())
+                .filter(|values| !values.is_empty())
+                .collect();
+
+            for (j, values) in ends.iter().enumerate() {
+                // the last end may be short when the arrows do not divide evenly
+                let expected = d.arrows_per_end.min(d.arrows_per_target().saturating_sub(j as u32 * d.arrows_per_end));
+                if values.len() > d.arrows_per_end as usize {
+                    res.push(Problem::TooManyArrows {
+                        target: i,
+                        end: j,
+                        expected: d.arrows_per_end,
+                        actual: values.len(),
+                    });
+                } else if values.len() < expected as usize {
+                    res.push(Problem::MissingArrows {
+                        target: i,
+                        end: j,
+                        expected,
+                        actual: values.len(),
+                    });
+                }
+
+                for (k, value) in values.iter().enumerate() {
+                    if !d.is_possible(*value) {
+                        res.push(Problem::ImpossibleValue {
+                            target: i,
+                            end: j,
+                            arrow: k,
+                            value: *value,
+                        });
+                    }
+                }
+            }
+
+            if ends.len() < d.ends() as usize {
+                res.push(Problem::MissingEnds {
+                    target: i,
+                    expected: d.ends(),
+                    actual: ends.len(),
+                });
+            } else if ends.len() > d.ends() as usize {
+                res.push(Problem::ExtraEnds {
+                    target: i,
+                    expected: d.ends(),
+                    actual: ends.len(),
+                });
+            }
+        }
+
+        if self.is_course_set() {
+            for (index, (d, count)) in self.distances.iter().zip(counts).enumerate() {
+                if count != d.targets as usize {
+                    res.push(Problem::WrongTargetsAtDistance {
+                        distance: index,
+                        expected: d.targets,
+                        actual: count,
+                    });
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Returns a Round of this definition with its Targets and no ends
+    ///
+    /// Where the course sets the distance there is a Target for each peg, with a distance of 0
+    /// to be filled in as each is shot, and where it sets the face size the face size is 0
+    pub fn new_round(&self) -> Round {
+        let mut targets = vec![];
+        for d in self.distances {
+            let face_size = d.face_size.unwrap_or(Length::new(0, LengthUnit::Centimetres));
+            let (name, distance) = if d.is_fixed() {
+                (d.max_distance.to_string(), d.max_distance)
+            } else {
+                (format!("{}-{}", d.min_distance, d.max_distance), Length::new(0, d.max_distance.unit))
+            };
+            let name = match d.face_size {
+                Some(size) => format!("{} {}", name, size),
+                None => name,
+            };
+
+            for _ in 0..d.targets {
+                targets.push(Target {
+                    face: Some(d.face),
+                    ..Target::new(name.clone(), distance, face_size)
+                });
+            }
+        }
+
+        Round {
+            name: self.name.to_string(),
+            targets,
+        }
+    }
+}
+
+/// Returns the standard round with the given name, ignoring case
+pub fn find(name: &str) -> Option<&'static RoundDefinition> {
+    ROUNDS.iter().find(|round| round.name.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Internal method for writing the catalogue compactly: a fixed distance
+const fn at(distance: Length, face: FaceKind, face_size: Length, arrows: u32, arrows_per_end: u32) -> DistanceDefinition {
+    DistanceDefinition {
+        min_distance: distance,
+        max_distance: distance,
+        face,
+        face_size: Some(face_size),
+        arrows,
+        arrows_per_end,
+        targets: 1,
+    }
+}
+
+/// Internal method for writing the catalogue compactly: a range of distances set by the
+/// course, shot at a number of pegs with the same arrows at each
+const fn between(min: Length, max: Length, face: FaceKind, face_size: Option<Length>, targets: u32, arrows: u32, arrows_per_end: u32) -> DistanceDefinition {
+    DistanceDefinition {
+        min_distance: min,
+        max_distance: max,
+        face,
+        face_size,
+        arrows: targets * arrows,
+        arrows_per_end,
+        targets,
+    }
+}
+
+const fn m(value: u32) -> Length {
+    Length { value, unit: LengthUnit::Metres }
+}
+
+const fn yd(value: u32) -> Length {
+    Length { value, unit: LengthUnit::Yards }
+}
+
+const fn cm(value: u32) -> Length {
+    Length { value, unit: LengthUnit::Centimetres }
+}
+
+const fn inches(value: u32) -> Length {
+    Length { value, unit: LengthUnit::Inches }
+}
+
+/// Every round this library knows, outdoor target rounds first
+pub const ROUNDS: &[RoundDefinition] = &[
+    RoundDefinition {
+        name: "WA 1440 (90m)",
+        indoor: false,
+        distances: &[
+            at(m(90), WaTenZone, cm(122), 36, 6),
+            at(m(70), WaTenZone, cm(122), 36, 6),
+            at(m(50), WaTenZone, cm(80), 36, 3),
+            at(m(30), WaTenZone, cm(80), 36, 3),
+        ],
+    },
+    RoundDefinition {
+        name: "WA 1440 (70m)",
+        indoor: false,
+        distances: &[
+            at(m(70), WaTenZone, cm(122), 36, 6),
+            at(m(60), WaTenZone, cm(122), 36, 6),
+            at(m(50), WaTenZone, cm(80), 36, 3),
+            at(m(30), WaTenZone, cm(80), 36, 3),
+        ],
+    },
+    RoundDefinition {
+        name: "WA 720 (70m)",
+        indoor: false,
+        distances: &[at(m(70), WaTenZone, cm(122), 72, 6)],
+    },
+    RoundDefinition {
+        name: "WA 720 (60m)",
+        indoor: false,
+        distances: &[at(m(60), WaTenZone, cm(122), 72, 6)],
+    },
+    RoundDefinition {
+        name: "WA 720 (50m)",
+        indoor: false,
+        distances: &[at(m(50), WaCompoundInnerTen, cm(80), 72, 6)],
+    },
+    RoundDefinition {
+        name: "WA 720 (50m barebow)",
+        indoor: false,
+        distances: &[at(m(50), WaTenZone, cm(122), 72, 6)],
+    },
+    RoundDefinition {
+        name: "York",
+        indoor: false,
+        distances: &[
+            at(yd(100), WaFiveZone, cm(122), 72, 6),
+            at(yd(80), WaFiveZone, cm(122), 48, 6),
+            at(yd(60), WaFiveZone, cm(122), 24, 6),
+        ],
+    },
+    RoundDefinition {
+        name: "Hereford",
+        indoor: false,
+        distances: &[
+            at(yd(80), WaFiveZone, cm(122), 72, 6),
+            at(yd(60), WaFiveZone, cm(122), 48, 6),
+            at(yd(50), WaFiveZone, cm(122), 24, 6),
+        ],
+    },
+    RoundDefinition {
+        name: "National",
+        indoor: false,
+        distances: &[
+            at(yd(60), WaFiveZone, cm(122), 48, 6),
+            at(yd(50), WaFiveZone, cm(122), 24, 6),
+        ],
+    },
+    RoundDefinition {
+        name: "Western",
+        indoor: false,
+        distances: &[
+            at(yd(60), WaFiveZone, cm(122), 48, 6),
+            at(yd(50), WaFiveZone, cm(122), 48, 6),
+        ],
+    },
+    RoundDefinition {
+        name: "Warwick",
+        indoor: false,
+        distances: &[
+            at(yd(60), WaFiveZone, cm(122), 24, 6),
+            at(yd(50), WaFiveZone, cm(122), 24, 6),
+        ],
+    },
+    RoundDefinition {
+        name: "American",
+        indoor: false,
+        distances: &[
+            at(yd(60), WaFiveZone, cm(122), 30, 6),
+            at(yd(50), WaFiveZone, cm(122), 30, 6),
+            at(yd(40), WaFiveZone, cm(122), 30, 6),
+        ],
+    },
+    RoundDefinition {
+        name: "WA 18",
+        indoor: true,
+        distances: &[at(m(18), WaTenZone, cm(40), 60, 3)],
+    },
+    RoundDefinition {
+        name: "WA 18 (triple spot)",
+        indoor: true,
+        distances: &[at(m(18), WaTripleSpot, cm(40), 60, 3)],
+    },
+    RoundDefinition {
+        name: "WA 25",
+        indoor: true,
+        distances: &[at(m(25), WaTenZone, cm(60), 60, 3)],
+    },
+    RoundDefinition {
+        name: "Portsmouth",
+        indoor: true,
+        distances: &[at(yd(20), WaTenZone, cm(60), 60, 3)],
+    },
+    RoundDefinition {
+        name: "Worcester",
+        indoor: true,
+        distances: &[at(yd(20), Worcester, inches(16), 60, 5)],
+    },
+    RoundDefinition {
+        name: "Vegas",
+        indoor: true,
+        distances: &[at(yd(20), WaTripleSpot, cm(40), 30, 3)],
+    },
+    RoundDefinition {
+        name: "NFAA 300",
+        indoor: true,
+        distances: &[at(yd(20), NfaaFiveSpot, cm(40), 60, 5)],
+    },
+    RoundDefinition {
+        name: "WA Field",
+        indoor: false,
+        distances: &[
+            between(m(5), m(15), WaField, Some(cm(20)), 6, 3, 3),
+            between(m(10), m(25), WaField, Some(cm(40)), 6, 3, 3),
+            between(m(20), m(35), WaField, Some(cm(60)), 6, 3, 3),
+            between(m(35), m(60), WaField, Some(cm(80)), 6, 3, 3),
+        ],
+    },
+    RoundDefinition {
+        name: "WA 3D",
+        indoor: false,
+        distances: &[between(m(5), m(45), ThreeD, None, 24, 2, 2)],
+    },
+];
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::session::{End, ValueScore};
+
+    #[test]
+    fn test_catalogue() {
+        for round in ROUNDS {
+            assert!(!round.distances.is_empty(), "{} has no distances", round.name);
+            assert_eq!(Some(round), find(round.name));
+
+            for d in round.distances {
+                assert!(d.min_distance.micrometres() <= d.max_distance.micrometres());
+                assert!(d.arrows_per_end > 0);
+                assert_eq!(0, d.arrows % d.targets, "{} splits arrows unevenly", round.name);
+            }
+        }
+
+        assert_eq!(1440, find("wa 1440 (90m)").unwrap().max_score());
+        assert_eq!(144, find("York").unwrap().arrows());
+        assert_eq!(1296, find("York").unwrap().max_score());
+        assert_eq!(300, find("NFAA 300").unwrap().max_score());
+        assert_eq!(None, find("Bray IV"));
+    }
+
+    #[test]
+    fn test_portsmouth() {
+        let portsmouth = find("Portsmouth").unwrap();
+        assert_eq!(20, portsmouth.distances[0].ends());
+        assert_eq!(5.0, portsmouth.distances[0].dozens());
+
+        let round = portsmouth.new_round();
+        assert_eq!("Portsmouth", round.name);
+        assert_eq!(1, round.targets.len());
+        assert_eq!("20yd 60cm", round.targets[0].name);
+        assert_eq!(Length::new(20, LengthUnit::Yards), round.targets[0].distance);
+        assert_eq!(Some(FaceKind::WaTenZone), round.targets[0].face);
+        assert!(round.targets[0].ends.is_empty());
+    }
+
+    /// Returns a Portsmouth with every arrow scoring the given value
+    /// Returns a Portsmouth of 60 arrows of the given value
+    pub(crate) fn shot_portsmouth(value: ScoreValue) -> Round {
+        let mut round = find("Portsmouth").unwrap().new_round();
+        for _ in 0..20 {
+            round.targets[0].ends.push(End::Scored(vec![ValueScore { value }; 3]));
+        }
+        round
+    }
+
+    #[test]
+    fn test_validate() {
+        let portsmouth = find("Portsmouth").unwrap();
+        assert_eq!(Vec::<Problem>::new(), portsmouth.validate(&shot_portsmouth(ScoreValue::Zone(9))));
+
+        // the same distance in other units is fine
+        let mut round = shot_portsmouth(ScoreValue::X(10));
+        round.targets[0].distance = Length::new(60, LengthUnit::Feet);
+        round.targets[0].ends.push(End::Blank(6));
+        assert_eq!(Vec::<Problem>::new(), portsmouth.validate(&round));
+    }
+
+    #[test]
+    fn test_validate_problems() {
+        let portsmouth = find("Portsmouth").unwrap();
+
+        let mut round = shot_portsmouth(ScoreValue::Zone(9));
+        round.targets[0].distance = Length::new(18, LengthUnit::Metres);
+        round.targets[0].face_size = Length::new(40, LengthUnit::Centimetres);
+        round.targets[0].ends.truncate(18);
+        round.targets[0].ends[0] = End::Scored(vec![ValueScore { value: ScoreValue::Zone(9) }; 4]);
+        round.targets[0].ends[1] = End::Scored(vec![ValueScore { value: ScoreValue::Zone(9) }; 2]);
+        round.targets[0].ends[2] = End::Scored(vec![
+            ValueScore { value: ScoreValue::Zone(11) },
+            ValueScore { value: ScoreValue::Hit },
+            ValueScore { value: ScoreValue::Miss },
+        ]);
+
+        assert_eq!(
+            vec![
+                Problem::WrongDistance {
+                    target: 0,
+                    min: Length::new(20, LengthUnit::Yards),
+                    max: Length::new(20, LengthUnit::Yards),
+                    actual: Length::new(18, LengthUnit::Metres),
+                },
+                Problem::WrongFaceSize {
+                    target: 0,
+                    expected: Length::new(60, LengthUnit::Centimetres),
+                    actual: Length::new(40, LengthUnit::Centimetres),
+                },
+                Problem::TooManyArrows { target: 0, end: 0, expected: 3, actual: 4 },
+                Problem::MissingArrows { target: 0, end: 1, expected: 3, actual: 2 },
+                Problem::ImpossibleValue { target: 0, end: 2, arrow: 0, value: ScoreValue::Zone(11) },
+                Problem::ImpossibleValue { target: 0, end: 2, arrow: 1, value: ScoreValue::Hit },
+                Problem::MissingEnds { target: 0, expected: 20, actual: 18 },
+            ],
+            portsmouth.validate(&round)
+        );
+
+        let field = find("WA Field").unwrap();
+        let mut round = field.new_round();
+        round.targets[0].distance = Length::new(3, LengthUnit::Metres);
+        assert_eq!(
+            Some(&Problem::WrongDistance {
+                target: 0,
+                min: Length::new(5, LengthUnit::Metres),
+                max: Length::new(15, LengthUnit::Metres),
+                actual: Length::new(3, LengthUnit::Metres),
+            }),
+            field.validate(&round).first()
+        );
+
+        let mut round = find("National").unwrap().new_round();
+        round.targets.pop();
+        assert_eq!(
+            Some(&Problem::WrongTargetCount { expected: 2, actual: 1 }),
+            find("National").unwrap().validate(&round).first()
+        );
+    }
+
+    #[test]
+    fn test_field() {
+        let field = find("WA Field").unwrap();
+        assert_eq!(24, field.targets());
+        assert_eq!(72, field.arrows());
+
+        let mut round = field.new_round();
+        assert_eq!(24, round.targets.len());
+        assert_eq!("5m-15m 20cm", round.targets[0].name);
+        assert_eq!(0, round.targets[0].distance.value);
+
+        // pegs in course order rather than by face size, each at its own distance
+        round.targets.reverse();
+        for (i, target) in round.targets.iter_mut().enumerate() {
+            let d = field.distances.iter().find(|d| d.allows_face_size(target.face_size)).unwrap();
+            target.distance = Length::new(d.min_distance.value + i as u32 % 3, LengthUnit::Metres);
+            target.ends.push(End::Scored(vec![ValueScore { value: ScoreValue::Zone(5) }; 3]));
+        }
+        assert_eq!(Vec::<Problem>::new(), field.validate(&round));
+
+        // a 20cm face shot in place of a 40cm one
+        round.targets.iter_mut().find(|target| target.face_size.value == 40).unwrap().face_size = Length::new(20, LengthUnit::Centimetres);
+        let problems = field.validate(&round);
+        assert!(problems.contains(&Problem::WrongTargetsAtDistance { distance: 0, expected: 6, actual: 7 }));
+        assert!(problems.contains(&Problem::WrongTargetsAtDistance { distance: 1, expected: 6, actual: 5 }));
+
+        let three_d = find("WA 3D").unwrap();
+        assert_eq!(24, three_d.new_round().targets.len());
+        assert_eq!(1, three_d.distances[0].ends());
+    }
+
+    #[test]
+    fn test_barebow_720() {
+        let round = find("WA 720 (50m barebow)").unwrap();
+        assert_eq!(720, round.max_score());
+        // the whole 10 ring scores 10 on the full face, and only the inner ring for compounds
+        assert!(round.distances[0].is_possible(ScoreValue::Zone(10)));
+        assert!(!find("WA 720 (50m)").unwrap().distances[0].is_possible(ScoreValue::Zone(10)));
+    }
+
+    #[test]
+    fn test_york_targets() {
+        let round = find("York").unwrap().new_round();
+        let distances: Vec<u32> = round.targets.iter().map(|t| t.distance.value).collect();
+
+        assert_eq!(vec![100, 80, 60], distances);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::face::{Face, Ring};
+use crate::session::{End, MeasuredScore, ScoreValue};
+
+/// A MeasuredScore whose recorded value is not what its position scores
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// Index of the End within the Target
+    pub end: usize,
+    /// Index of the arrow within the End
+    pub arrow: usize,
+    pub recorded: ScoreValue,
+    /// The value the position scores
+    pub scored: ScoreValue,
+}
+
+/// Scores plotted arrows on a face, where an arrow touching the line between two zones scores
+/// the higher
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scorer {
+    pub face: Face,
+    /// Diameter of the arrow shaft, in millimetres
+    pub arrow_diameter_mm: f64,
+}
+
+impl Scorer {
+    pub fn new(face: Face, arrow_diameter_mm: f64) -> Scorer {
+        Scorer {
+            face,
+            arrow_diameter_mm,
+        }
+    }
+
+    /// Returns the ring an arrow centred on a point scores, given in millimetres from the centre
+    /// of the face, or None if it touches no ring
+    pub fn ring_at(&self, x: f64, y: f64) -> Option<Ring> {
+        // the nearest edge of the shaft decides the score
+        let distance = self.face.distance_to_spot_mm(x, y) - self.arrow_diameter_mm / 2.0;
+        self.face.kind.rings().iter().find(|ring| distance <= self.face.radius_mm(ring)).copied()
+    }
+
+    /// Returns the ring the position of a MeasuredScore scores
+    pub fn ring(&self, score: &MeasuredScore) -> Option<Ring> {
+        let (x, y) = score.position_mm();
+        self.ring_at(x, y)
+    }
+
+    /// Returns the value the position of a MeasuredScore scores
+    pub fn value(&self, score: &MeasuredScore) -> ScoreValue {
+        self.ring(score).map_or(ScoreValue::Miss, |ring| ring.score_value())
+    }
+
+    /// Returns every MeasuredScore in the given ends whose recorded value contradicts its
+    /// position, ignoring ends of other kinds
+    pub fn check(&self, ends: &[End]) -> Vec<Mismatch> {
+        let mut res = vec![];
+
+        for (end, contents) in ends.iter().enumerate() {
+            if let End::Measured(scores) = contents {
+                for (arrow, score) in scores.iter().enumerate() {
+                    let scored = self.value(score);
+                    if scored != score.value {
+                        res.push(Mismatch {
+                            end,
+                            arrow,
+                            recorded: score.value,
+                            scored,
+                        });
+                    }
+                }
+            }
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::face::FaceKind;
+    use crate::units::{Length, LengthUnit};
+
+    fn scorer() -> Scorer {
+        // 10 ring radius 40 mm, 1 ring 400 mm
+        Scorer::new(Face::new(FaceKind::WaTenZone, Length::new(80, LengthUnit::Centimetres)), 5.0)
+    }
+
+    fn measured(value: ScoreValue, r: u32, theta: u32) -> MeasuredScore {
+        MeasuredScore {
+            value,
+            r,
+            theta,
+        }
+    }
+
+    #[test]
+    fn test_line_cutter() {
+        let scorer = scorer();
+
+        // the shaft reaches 39.5 mm from the centre, cutting the 10 ring's line
+        assert_eq!(ScoreValue::Zone(10), scorer.value(&measured(ScoreValue::Miss, 4200, 0)));
+        assert_eq!(ScoreValue::Zone(9), scorer.value(&measured(ScoreValue::Miss, 4300, 18000)));
+        // positions are precise enough to split arrows a hundredth of a millimetre apart
+        assert_eq!(ScoreValue::Zone(10), scorer.value(&measured(ScoreValue::Miss, 4250, 0)));
+        assert_eq!(ScoreValue::Zone(9), scorer.value(&measured(ScoreValue::Miss, 4251, 0)));
+        assert_eq!(ScoreValue::X(10), scorer.value(&measured(ScoreValue::Miss, 2200, 9000)));
+        assert_eq!(ScoreValue::Zone(1), scorer.value(&measured(ScoreValue::Miss, 40200, 27000)));
+        assert_eq!(ScoreValue::Miss, scorer.value(&measured(ScoreValue::Miss, 40300, 27000)));
+    }
+
+    #[test]
+    fn test_position() {
+        let (x, y) = measured(ScoreValue::Miss, 10000, 9000).position_mm();
+        assert!(x.abs() < 1e-9);
+        assert!((y - 100.0).abs() < 1e-9);
+
+        let (x, y) = measured(ScoreValue::Miss, 10000, 18000).position_mm();
+        assert!((x + 100.0).abs() < 1e-9);
+        assert!(y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_check() {
+        let ends = vec![
+            End::Blank(6),
+            End::Measured(vec![
+                measured(ScoreValue::X(10), 1000, 0),
+                measured(ScoreValue::Zone(10), 5000, 9000),
+                measured(ScoreValue::Miss, 50000, 0),
+            ]),
+        ];
+
+        assert_eq!(
+            vec![Mismatch {
+                end: 1,
+                arrow: 1,
+                recorded: ScoreValue::Zone(10),
+                scored: ScoreValue::Zone(9),
+            }],
+            scorer().check(&ends)
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use crate::buffer::{Buffer, BufferError, LengthEncoding, Reader};
+
+/// A record which can be written to and read from the file format
+///
+/// This is the supported way to encode a single part of a Session, such as an `End` streamed to
+/// a scoreboard, and to embed custom data in a Session with `Extension`:
+///
+/// ```
+/// use open_archery_format::Serialisable;
+/// use open_archery_format::buffer::Reader;
+/// use open_archery_format::session::End;
+///
+/// let data = End::BowDraws(20).serialise().unwrap();
+/// assert_eq!(End::BowDraws(20), End::deserialise(&mut data.reader()).unwrap());
+///
+/// #[derive(Serialisable, Debug, PartialEq)]
+/// struct Weather {
+///     #[oaf(varint)]
+///     wind_speed: u32,
+///     notes: String,
+/// }
+///
+/// let weather = Weather { wind_speed: 12, notes: "gusty".to_string() };
+/// let data = weather.serialise().unwrap().take_underlying_buffer();
+/// assert_eq!(weather, Weather::deserialise(&mut Reader::new(&data)).unwrap());
+/// ```
+///
+/// Implementations write and read the layout of the format version carried by the Buffer or
+/// Reader, so files from older versions decode straight into the current types. Most records can
+/// derive this with `#[derive(Serialisable)]`, which writes each field in declaration order.
+pub trait Serialisable: Sized {
+    /// Appends self as bytes to the provided Buffer
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError>;
+    /// Returns a copy of self built from the bytes in the provided Reader
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError>;
+
+    /// Returns a Buffer containing self as bytes in the current format version
+    fn serialise(&self) -> Result<Buffer, BufferError> {
+        let mut res = Buffer::new();
+        self.serialise_into(&mut res)?;
+        Ok(res)
+    }
+}
+
+macro_rules! fixed_width {
+    ($($t:ty => $append:ident, $pop:ident;)*) => {
+        $(
+            impl Serialisable for $t {
+                fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+                    res.$append(*self);
+                    Ok(())
+                }
+
+                fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
+                    data.$pop()
+                }
+            }
+        )*
+    };
+}
+
+fixed_width! {
+    u8 => append_u8, pop_u8;
+    u16 => append_u16, pop_u16;
+    u32 => append_u32, pop_u32;
+    u64 => append_u64, pop_u64;
+    f32 => append_f32, pop_f32;
+}
+
+/// Values written as a length followed by their contents, so the derive macro can choose how the
+/// length is written
+pub trait Sequence: Sized {
+    fn serialise_with(&self, res: &mut Buffer, lengths: LengthEncoding) -> Result<(), BufferError>;
+    fn deserialise_with(data: &mut Reader, lengths: LengthEncoding) -> Result<Self, BufferError>;
+}
+
+impl Sequence for String {
+    fn serialise_with(&self, res: &mut Buffer, lengths: LengthEncoding) -> Result<(), BufferError> {
+        res.append_len(self.len(), lengths)?;
+        res.append_bytes(self.as_bytes());
+        Ok(())
+    }
+
+    fn deserialise_with(data: &mut Reader, lengths: LengthEncoding) -> Result<Self, BufferError> {
+        let len = data.pop_len(lengths)?;
+        let bytes = data.pop_n_bytes(len)?;
+        Ok(std::str::from_utf8(bytes).map_err(BufferError::InvalidUtf8String)?.to_string())
+    }
+}
+
+impl<T: Serialisable> Sequence for Vec<T> {
+    fn serialise_with(&self, res: &mut Buffer, lengths: LengthEncoding) -> Result<(), BufferError> {
+        res.append_len(self.len(), lengths)?;
+        for item in self {
+            item.serialise_into(res)?;
+        }
+        Ok(())
+    }
+
+    fn deserialise_with(data: &mut Reader, lengths: LengthEncoding) -> Result<Self, BufferError> {
+        let mut res = vec![];
+        let read = data.pop_len(lengths)?;
+
+        for _ in 0..read {
+            res.push(T::deserialise(data)?);
+        }
+
+        Ok(res)
+    }
+}
+
+impl Serialisable for String {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        self.serialise_with(res, LengthEncoding::Version)
+    }
+
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
+        String::deserialise_with(data, LengthEncoding::Version)
+    }
+}
+
+impl<T: Serialisable> Serialisable for Vec<T> {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        self.serialise_with(res, LengthEncoding::Version)
+    }
+
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
+        Vec::deserialise_with(data, LengthEncoding::Version)
+    }
+}
+
+/// Optional values are written as a presence byte, 0 or 1, followed by the value if present
+impl<T: Serialisable> Serialisable for Option<T> {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        match self {
+            None => res.append_u8(0),
+            Some(value) => {
+                res.append_u8(1);
+                value.serialise_into(res)?;
+            },
+        }
+        Ok(())
+    }
+
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
+        let offset = data.position();
+        match data.pop_u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::deserialise(data)?)),
+            tag => Err(BufferError::UnknownTag { tag, offset }),
+        }
+    }
+}
+
+/// Integers which the derive macro can write as varints with `#[oaf(varint)]`
+pub trait Varint: Sized {
+    fn to_varint(&self) -> u64;
+    fn from_varint(n: u64) -> Result<Self, BufferError>;
+}
+
+macro_rules! varint {
+    ($($t:ty),*) => {
+        $(
+            impl Varint for $t {
+                fn to_varint(&self) -> u64 {
+                    *self as u64
+                }
+
+                fn from_varint(n: u64) -> Result<Self, BufferError> {
+                    <$t>::try_from(n).map_err(|_| BufferError::VarintTooLong)
+                }
+            }
+        )*
+    };
+}
+
+varint!(u8, u16, u32, u64, usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use open_archery_format_derive::Serialisable;
+
+    #[derive(Serialisable, Debug, PartialEq)]
+    struct Record {
+        small: u8,
+        #[oaf(varint)]
+        count: u32,
+        #[oaf(len = "u16")]
+        name: String,
+        items: Vec<u16>,
+    }
+
+    #[derive(Serialisable, Debug, PartialEq)]
+    enum Kind {
+        #[oaf(tag = 3)]
+        Empty,
+        #[oaf(tag = 0)]
+        Pair(u8, #[oaf(varint)] u64),
+        #[oaf(tag = 7)]
+        Named {
+            record: Record,
+        },
+    }
+
+    /// Fields named like the parameters and locals of the generated code
+    #[derive(Serialisable, Debug, PartialEq)]
+    struct Scan {
+        res: u32,
+        data: u8,
+        offset: u16,
+    }
+
+    #[derive(Serialisable, Debug, PartialEq)]
+    enum Clash {
+        #[oaf(tag = 0)]
+        Fields {
+            res: u32,
+            tag: u8,
+        },
+    }
+
+    fn record() -> Record {
+        Record {
+            small: 1,
+            count: 300,
+            name: "ab".to_string(),
+            items: vec![5, 6],
+        }
+    }
+
+    #[test]
+    fn test_derive_struct() {
+        let data = record().serialise().unwrap();
+        assert_eq!(data, vec![1, 172, 2, 2, 0, 97, 98, 2, 5, 0, 6, 0]);
+        assert_eq!(record(), Record::deserialise(&mut data.reader()).unwrap());
+    }
+
+    #[test]
+    fn test_derive_struct_old_version() {
+        let mut data = Buffer::with_version(Version::V0_1_0);
+        record().serialise_into(&mut data).unwrap();
+
+        // only the length of the vector follows the format version
+        assert_eq!(data, vec![1, 172, 2, 2, 0, 97, 98, 2, 0, 5, 0, 6, 0]);
+        assert_eq!(record(), Record::deserialise(&mut data.reader()).unwrap());
+    }
+
+    #[test]
+    fn test_derive_enum() {
+        for kind in [Kind::Empty, Kind::Pair(4, 1000), Kind::Named { record: record() }] {
+            let data = kind.serialise().unwrap();
+            assert_eq!(kind, Kind::deserialise(&mut data.reader()).unwrap());
+        }
+
+        assert_eq!(Kind::Empty.serialise().unwrap(), vec![3]);
+        assert_eq!(Kind::Pair(4, 1000).serialise().unwrap(), vec![0, 4, 232, 7]);
+    }
+
+    #[test]
+    fn test_derive_hygiene() {
+        let scan = Scan { res: 1, data: 2, offset: 3 };
+        assert_eq!(scan, Scan::deserialise(&mut scan.serialise().unwrap().reader()).unwrap());
+
+        let clash = Clash::Fields { res: 1, tag: 2 };
+        assert_eq!(clash, Clash::deserialise(&mut clash.serialise().unwrap().reader()).unwrap());
+    }
+
+    #[test]
+    fn test_derive_enum_unknown_tag() {
+        let res = Kind::deserialise(&mut Reader::new(&[9]));
+        assert_eq!(Err(BufferError::UnknownTag { tag: 9, offset: 0 }), res);
+    }
+
+    #[test]
+    fn test_option() {
+        for value in [None, Some(7u16)] {
+            let data = value.serialise().unwrap();
+            assert_eq!(value, Option::deserialise(&mut data.reader()).unwrap());
+        }
+
+        assert_eq!(Some(7u16).serialise().unwrap(), vec![1, 7, 0]);
+        assert_eq!(Err(BufferError::UnknownTag { tag: 2, offset: 0 }), Option::<u16>::deserialise(&mut Reader::new(&[2])));
+    }
+
+    #[test]
+    fn test_varint_too_big() {
+        assert_eq!(Err(BufferError::VarintTooLong), u8::from_varint(256));
+        assert_eq!(Ok(255), u8::from_varint(255));
+    }
+}
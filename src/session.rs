@@ -1,29 +1,106 @@
+use std::fmt;
 use std::fs::File;
-use std::hash::{Hash, Hasher};
 use std::io::{self, Read, Write};
+use std::path::Path;
 // structs should implement the Binary trait
-use crate::buffer::{Buffer, BufferError};
-
-trait Serialisable<T> {
-    /// Returns a Buffer containing self as bytes
-    fn serialise(&self) -> Result<Buffer, BufferError>;
-    /// Returns a copy of self built from the bytes in the provided Buffer
-    fn deserialise(data: &mut Buffer) -> Result<T, BufferError>;
-}
+use open_archery_format_derive::Serialisable;
+use crate::archer::Archer;
+use crate::bow::Bow;
+use crate::buffer::{Buffer, BufferError, Reader};
+use crate::checksum::crc32c;
+use crate::date::{SessionDate, Time};
+use crate::face::{Face, FaceKind};
+use crate::migrate;
+use crate::serialise::Serialisable;
+use crate::units::{Length, LengthUnit};
+use crate::version::Version;
+
+/// Magic bytes at the start of every file: 4F 41 46 46 (OAFF)
+const MAGIC: [u8; 4] = *b"OAFF";
+
+/// Tags of the chunks making up the body from format 0.4.0
+const SESSION_CHUNK: [u8; 4] = *b"SESS";
+const ROUND_CHUNK: [u8; 4] = *b"ROND";
+const TARGET_CHUNK: [u8; 4] = *b"TARG";
+const ENDS_CHUNK: [u8; 4] = *b"ENDS";
+const BOW_CHUNK: [u8; 4] = *b"EQIP";
+const ARCHER_CHUNK: [u8; 4] = *b"ARCH";
+const END_TIMES_CHUNK: [u8; 4] = *b"ETIM";
+const FACE_CHUNK: [u8; 4] = *b"FACE";
 
 #[derive(Debug, PartialEq)]
 pub struct Session {
-    pub date: String,
+    /// When the session was shot, None if unknown
+    pub date: Option<SessionDate>,
+    /// The free-text date of a file written before format 0.5.0, kept when it could not be
+    /// read as a date
+    pub legacy_date: Option<String>,
     pub location: String,
-    // pub bow: Bow,
-    // pub archer: Archer,
+    /// The equipment shot with, only written from format 0.4.0
+    pub bow: Option<Bow>,
+    /// Whose scores these are, or None for an anonymous file; only written from format 0.4.0
+    pub archer: Option<Archer>,
     pub rounds: Vec<Round>,
+    /// Custom data embedded by other applications, only written from format 0.4.0
+    pub extensions: Vec<Extension>,
+}
+
+/// Custom data embedded in a Session by another application, written as a chunk under its own
+/// tag
+///
+/// Tags starting with an upper case letter are reserved for this library, so extension tags
+/// must start with a lower case one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extension {
+    pub tag: [u8; 4],
+    pub bytes: Vec<u8>,
+}
+
+impl Extension {
+    /// Returns an Extension holding the given value, written in the current format version
+    pub fn new<T: Serialisable>(tag: [u8; 4], value: &T) -> Result<Extension, BufferError> {
+        if !Extension::is_extension_tag(tag) {
+            return Err(BufferError::ReservedTag(tag));
+        }
+
+        Ok(Extension {
+            tag,
+            bytes: value.serialise()?.take_underlying_buffer(),
+        })
+    }
+
+    /// Returns the value held, read as a T in the current format version
+    pub fn read<T: Serialisable>(&self) -> Result<T, BufferError> {
+        T::deserialise(&mut Reader::new(&self.bytes))
+    }
+
+    pub fn is_extension_tag(tag: [u8; 4]) -> bool {
+        tag[0].is_ascii_lowercase()
+    }
 }
 
 #[derive(Debug)]
 pub enum FileError {
     BufferError(BufferError),
     IOError(io::Error),
+    /// The file does not start with the OAFF magic bytes
+    BadMagic,
+    /// The file was written with a newer format version than this library understands
+    UnsupportedVersion {
+        major: u8,
+        minor: u8,
+        patch: u8,
+    },
+    /// The body length in the header does not match the bytes present
+    LengthMismatch {
+        expected: u64,
+        actual: u64,
+    },
+    /// The checksum stored after the body does not match the body, so the file is corrupt
+    ChecksumMismatch {
+        expected: u32,
+        actual: u32,
+    },
 }
 
 impl From<BufferError> for FileError {
@@ -38,83 +115,335 @@ impl From<io::Error> for FileError {
     }
 }
 
+/// Options controlling how a Session is written
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodeOptions {
+    /// Format version to write, for apps which cannot read the current one yet
+    pub version: Version,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        EncodeOptions {
+            version: Version::CURRENT,
+        }
+    }
+}
+
+/// Options controlling how a Session is read
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeOptions {
+    /// Whether to reject files whose body does not match its checksum; turn off to recover what
+    /// can still be read from a corrupt file
+    pub verify_checksum: bool,
+    /// Whether to keep Ends of kinds this library does not know as `End::Unknown` rather than
+    /// rejecting the file; only files from format 0.4.0 frame Ends so they can be kept
+    pub keep_unknown: bool,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        DecodeOptions {
+            verify_checksum: true,
+            keep_unknown: false,
+        }
+    }
+}
+
 impl Session {
-    pub fn encode(&self, filename: String) -> Result<(), FileError> {
-        let mut res = Buffer::new();
+    /// Returns a Session at the given location with no date, equipment, archer or rounds
+    pub fn new(location: String) -> Session {
+        Session {
+            date: None,
+            legacy_date: None,
+            location,
+            bow: None,
+            archer: None,
+            rounds: vec![],
+            extensions: vec![],
+        }
+    }
+
+    /// Writes self to the file at the given path, creating or truncating it
+    pub fn encode(&self, path: impl AsRef<Path>) -> Result<(), FileError> {
+        self.write_to(File::create(path)?)
+    }
+
+    /// Reads a Session from the file at the given path
+    pub fn decode(path: impl AsRef<Path>) -> Result<Self, FileError> {
+        Self::read_from(File::open(path)?)
+    }
+
+    /// Writes self, including the file header, to the given writer
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<(), FileError> {
+        self.write_to_with(writer, &EncodeOptions::default())
+    }
 
-        // Magic bytes: 4F 41 46 46 (OAFF)
-        res.append_u8(0x4f); // O
-        res.append_u8(0x41); // A
-        res.append_u8(0x46); // F
-        res.append_u8(0x46); // F
+    /// Writes self, including the file header, to the given writer using the given options
+    pub fn write_to_with<W: Write>(&self, mut writer: W, options: &EncodeOptions) -> Result<(), FileError> {
+        Ok(writer.write_all(&self.to_bytes_with(options)?)?)
+    }
 
-        // Version: 0.1.0
-        res.append_u8(0); // 0
-        res.append_u8(1); // 1
-        res.append_u8(0); // 0
+    /// Reads a Session, including the file header, from the given reader
+    ///
+    /// Only the header and the number of body bytes it declares are read, so anything following
+    /// the Session in the stream is left unread
+    pub fn read_from<R: Read>(reader: R) -> Result<Self, FileError> {
+        Self::read_from_with(reader, &DecodeOptions::default())
+    }
 
-        let mut data = self.serialise()?;
+    /// Reads a Session, including the file header, from the given reader using the given options
+    pub fn read_from_with<R: Read>(mut reader: R, options: &DecodeOptions) -> Result<Self, FileError> {
+        let mut data = vec![];
+        reader.by_ref().take(HEADER_LENGTH as u64).read_to_end(&mut data)?;
+
+        let mut header = Reader::new(&data);
+        let expected = read_header(&mut header)?;
+        let footer = header.version().footer_length() as u64;
+        // a declared length too large to ever be present is not worth reading towards
+        let Some(length) = expected.checked_add(footer) else {
+            return Err(FileError::LengthMismatch { expected, actual: 0 });
+        };
+        reader.take(length).read_to_end(&mut data)?;
+
+        Self::from_bytes_with(&data, options)
+    }
+
+    /// Returns self as the bytes of a complete file
+    pub fn to_bytes(&self) -> Result<Vec<u8>, FileError> {
+        self.to_bytes_with(&EncodeOptions::default())
+    }
+
+    /// Returns self as the bytes of a complete file using the given options
+    ///
+    /// Data which cannot be represented in an older format version is left out
+    pub fn to_bytes_with(&self, options: &EncodeOptions) -> Result<Vec<u8>, FileError> {
+        let version = options.version;
+        if !version.is_supported() {
+            return Err(FileError::UnsupportedVersion {
+                major: version.major,
+                minor: version.minor,
+                patch: version.patch,
+            });
+        }
+
+        let mut res = Buffer::with_version(version);
+
+        for b in MAGIC {
+            res.append_u8(b);
+        }
+
+        res.append_u8(version.major);
+        res.append_u8(version.minor);
+        res.append_u8(version.patch);
+
+        let mut data = Buffer::with_version(version);
+        if version < Version::V0_4_0 {
+            self.serialise_into(&mut data)?;
+        } else {
+            data.append_chunk(SESSION_CHUNK, |data| self.serialise_into(data))?;
+        }
 
         res.append_u64(data.length() as u64);
+        let checksum = crc32c(data.bytes());
         res.append(&mut data);
 
-        let mut f = File::create(filename)?;
-        Ok(f.write_all(res.take_underlying_buffer().as_slice())?)
+        if version.footer_length() != 0 {
+            res.append_u32(checksum);
+        }
+
+        Ok(res.take_underlying_buffer())
     }
 
-    pub fn decode(filename: String) -> Result<Self, FileError> {
-        let mut f = File::open(filename)?;
+    /// Reads a Session from the bytes of a complete file
+    pub fn from_bytes(data: &[u8]) -> Result<Self, FileError> {
+        Self::from_bytes_with(data, &DecodeOptions::default())
+    }
 
-        let mut data = vec![];
+    /// Reads a Session from the bytes of a complete file using the given options
+    pub fn from_bytes_with(data: &[u8], options: &DecodeOptions) -> Result<Self, FileError> {
+        let mut reader = Reader::new(data);
+        let expected = read_header(&mut reader)?;
+
+        read_body(reader, expected, options)
+    }
+}
+
+/// Length of the magic bytes, version and body length at the start of every file
+const HEADER_LENGTH: usize = MAGIC.len() + 3 + 8;
+
+/// Checks the magic bytes and version, returning the body length declared by the header
+///
+/// The Reader is switched to the version of the file so the body is read the way it was written
+fn read_header(buf: &mut Reader) -> Result<u64, FileError> {
+    if buf.pop_n_bytes(MAGIC.len()).map_err(|_| FileError::BadMagic)? != MAGIC {
+        return Err(FileError::BadMagic);
+    }
 
-        f.read_to_end(&mut data)?;
+    let version = Version::new(buf.pop_u8()?, buf.pop_u8()?, buf.pop_u8()?);
+    if !version.is_supported() {
+        return Err(FileError::UnsupportedVersion {
+            major: version.major,
+            minor: version.minor,
+            patch: version.patch,
+        });
+    }
+    buf.set_version(version);
 
-        let mut buf = Buffer::from(data);
-        buf.pop_n_bytes(4)?;
+    Ok(buf.pop_u64()?)
+}
 
-        let v1 = buf.pop_u8()?;
-        let v2 = buf.pop_u8()?;
-        let v3 = buf.pop_u8()?;
+/// Reads a Session from a body which should be exactly `expected` bytes long, followed by the
+/// footer of the Reader's version, upgrading it from that version to the current model
+fn read_body(mut buf: Reader, expected: u64, options: &DecodeOptions) -> Result<Session, FileError> {
+    let footer = buf.version().footer_length();
+    let actual = buf.remaining().saturating_sub(footer) as u64;
+    if expected != actual || buf.remaining() < footer {
+        return Err(FileError::LengthMismatch { expected, actual });
+    }
 
-        println!("Encoded with v{v1}.{v2}.{v3}");
+    let mut body = buf.pop_reader(actual as usize)?;
 
-        println!("Session has length {:?}", buf.pop_u64()?);
+    if footer != 0 {
+        let stored = buf.pop_u32()?;
+        let computed = crc32c(body.rest());
+        if options.verify_checksum && stored != computed {
+            return Err(FileError::ChecksumMismatch {
+                expected: stored,
+                actual: computed,
+            });
+        }
+    }
 
-        Ok(Session::deserialise(&mut buf)?)
+    body.set_lenient(options.keep_unknown);
+
+    let mut session = if body.version() < Version::V0_4_0 {
+        Session::deserialise(&mut body)?
+    } else {
+        read_session_chunk(&mut body)?
+    };
+    migrate::upgrade(&mut session, body.version());
+
+    // the body ended before the declared length was used up
+    if body.remaining() != 0 {
+        return Err(FileError::LengthMismatch {
+            expected,
+            actual: actual - body.remaining() as u64,
+        });
     }
+
+    Ok(session)
 }
 
-impl Serialisable<Session> for Session {
-    fn serialise(&self) -> Result<Buffer, BufferError> {
-        let mut res = Buffer::new();
+/// Reads the Session chunk from a chunked body, skipping any other chunks
+fn read_session_chunk(body: &mut Reader) -> Result<Session, BufferError> {
+    let mut session = None;
+    while body.remaining() > 0 {
+        let (tag, mut chunk) = body.pop_chunk()?;
+        if tag == SESSION_CHUNK && session.is_none() {
+            session = Some(Session::deserialise(&mut chunk)?);
+        }
+    }
 
-        res.append_string(&self.date)?;
+    session.ok_or(BufferError::MissingChunk(SESSION_CHUNK))
+}
+
+impl Serialisable for Session {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        if res.version() < Version::V0_5_0 {
+            let date = match (&self.legacy_date, &self.date) {
+                (Some(text), _) => text.clone(),
+                (None, Some(date)) => date.to_string(),
+                (None, None) => String::new(),
+            };
+            res.append_string(&date)?;
+        } else {
+            self.date.serialise_into(res)?;
+            self.legacy_date.serialise_into(res)?;
+        }
 
         res.append_string(&self.location)?;
 
-        res.append_usize(self.rounds.len())?;
-        for round in &self.rounds {
-            res.append(&mut round.serialise()?);
+        if res.version() < Version::V0_4_0 {
+            res.append_usize(self.rounds.len())?;
+            for round in &self.rounds {
+                round.serialise_into(res)?;
+            }
+        } else {
+            if let Some(bow) = &self.bow {
+                res.append_chunk(BOW_CHUNK, |res| bow.serialise_into(res))?;
+            }
+
+            if let Some(archer) = &self.archer {
+                res.append_chunk(ARCHER_CHUNK, |res| archer.serialise_into(res))?;
+            }
+
+            for round in &self.rounds {
+                res.append_chunk(ROUND_CHUNK, |res| round.serialise_into(res))?;
+            }
+
+            for extension in &self.extensions {
+                if !Extension::is_extension_tag(extension.tag) {
+                    return Err(BufferError::ReservedTag(extension.tag));
+                }
+
+                res.append_chunk(extension.tag, |res| {
+                    res.append_bytes(&extension.bytes);
+                    Ok(())
+                })?;
+            }
         }
 
-        Ok(res)
+        Ok(())
     }
 
-    fn deserialise(data: &mut Buffer) -> Result<Self, BufferError> {
-        let date = data.pop_string()?;
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
+        let (date, legacy_date) = if data.version() < Version::V0_5_0 {
+            // interpreted by the migration to 0.5.0
+            let text = data.pop_string()?;
+            (None, if text.is_empty() { None } else { Some(text) })
+        } else {
+            (Option::deserialise(data)?, Option::deserialise(data)?)
+        };
         let location = data.pop_string()?;
 
+        let mut bow = None;
+        let mut archer = None;
         let mut rounds = vec![];
-        let read = data.pop_usize()?;
+        let mut extensions = vec![];
+
+        if data.version() < Version::V0_4_0 {
+            let read = data.pop_usize()?;
 
-        for _ in 0..read {
-            rounds.push(Round::deserialise(data)?);
+            for _ in 0..read {
+                rounds.push(Round::deserialise(data)?);
+            }
+        } else {
+            while data.remaining() > 0 {
+                let (tag, mut chunk) = data.pop_chunk()?;
+                if tag == BOW_CHUNK {
+                    bow = Some(Bow::deserialise(&mut chunk)?);
+                } else if tag == ARCHER_CHUNK {
+                    archer = Some(Archer::deserialise(&mut chunk)?);
+                } else if tag == ROUND_CHUNK {
+                    rounds.push(Round::deserialise(&mut chunk)?);
+                } else if Extension::is_extension_tag(tag) {
+                    extensions.push(Extension {
+                        tag,
+                        bytes: chunk.rest().to_vec(),
+                    });
+                }
+            }
         }
 
         Ok(Session {
             date,
+            legacy_date,
             location,
+            bow,
+            archer,
             rounds,
+            extensions,
         })
     }
 }
@@ -125,28 +454,42 @@ pub struct Round {
     pub targets: Vec<Target>,
 }
 
-impl Serialisable<Round> for Round {
-    fn serialise(&self) -> Result<Buffer, BufferError> {
-        let mut res = Buffer::new();
-
+impl Serialisable for Round {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
         res.append_string(&self.name)?;
 
-        res.append_usize(self.targets.len())?;
-        for target in &self.targets {
-            res.append(&mut target.serialise()?);
+        if res.version() < Version::V0_4_0 {
+            res.append_usize(self.targets.len())?;
+            for target in &self.targets {
+                target.serialise_into(res)?;
+            }
+        } else {
+            for target in &self.targets {
+                res.append_chunk(TARGET_CHUNK, |res| target.serialise_into(res))?;
+            }
         }
 
-        Ok(res)
+        Ok(())
     }
 
-    fn deserialise(data: &mut Buffer) -> Result<Self, BufferError> {
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
         let name = data.pop_string()?;
 
         let mut targets = vec![];
-        let read = data.pop_usize()?;
 
-        for _ in 0..read {
-            targets.push(Target::deserialise(data)?);
+        if data.version() < Version::V0_4_0 {
+            let read = data.pop_usize()?;
+
+            for _ in 0..read {
+                targets.push(Target::deserialise(data)?);
+            }
+        } else {
+            while data.remaining() > 0 {
+                let (tag, mut chunk) = data.pop_chunk()?;
+                if tag == TARGET_CHUNK {
+                    targets.push(Target::deserialise(&mut chunk)?);
+                }
+            }
         }
 
         Ok(Round {
@@ -159,63 +502,179 @@ impl Serialisable<Round> for Round {
 #[derive(Debug, PartialEq)]
 pub struct Target {
     pub name: String,
-    pub distance: u32,
-    pub distance_unit: String,
-    pub face_size: u32,
-    pub face_size_unit: String,
+    pub distance: Length,
+    pub face_size: Length,
+    /// The design of the face shot at, if known; only written from format 0.4.0
+    pub face: Option<FaceKind>,
     pub inclination: u32,
     pub ends: Vec<End>,
+    /// Either empty, or the time of day each End in `ends` was shot, one for each End; a Target
+    /// with any other number of times cannot be written or read. Only written from format 0.4.0
+    pub end_times: Vec<Option<Time>>,
 }
 
-impl Serialisable<Target> for Target {
-    fn serialise(&self) -> Result<Buffer, BufferError> {
-        let mut res = Buffer::new();
+impl Target {
+    /// Returns a Target at the given distance and face size with no ends and nothing else
+    /// recorded
+    pub fn new(name: String, distance: Length, face_size: Length) -> Target {
+        Target {
+            name,
+            distance,
+            face_size,
+            face: None,
+            inclination: 0,
+            ends: vec![],
+            end_times: vec![],
+        }
+    }
+
+    /// Returns the face shot at, scaled to `face_size`, if its design is known
+    pub fn face_geometry(&self) -> Option<Face> {
+        self.face.map(|kind| Face::new(kind, self.face_size))
+    }
 
+    /// Returns the time of day the End at the given index was shot, if recorded
+    pub fn end_time(&self, index: usize) -> Option<Time> {
+        self.end_times.get(index).copied().flatten()
+    }
+
+    /// Appends an End shot at the given time of day, keeping `end_times` in step with `ends`
+    pub fn push_end(&mut self, end: End, time: Option<Time>) {
+        if time.is_some() && self.end_times.is_empty() {
+            self.end_times.resize(self.ends.len(), None);
+        }
+        if !self.end_times.is_empty() {
+            self.end_times.push(time);
+        }
+
+        self.ends.push(end);
+    }
+
+    /// Internal method for checking there is either no end time or one for each End
+    fn check_end_times(&self) -> Result<(), BufferError> {
+        if !self.end_times.is_empty() && self.end_times.len() != self.ends.len() {
+            return Err(BufferError::EndTimesMismatch {
+                ends: self.ends.len(),
+                times: self.end_times.len(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Internal method for writing a count followed by each End
+fn serialise_ends(ends: &[&End], res: &mut Buffer) -> Result<(), BufferError> {
+    res.append_usize(ends.len())?;
+    for end in ends {
+        end.serialise_into(res)?;
+    }
+
+    Ok(())
+}
+
+/// Internal method for reading a count followed by each End
+fn deserialise_ends(data: &mut Reader) -> Result<Vec<End>, BufferError> {
+    let mut ends = vec![];
+    let read = data.pop_usize()?;
+
+    for _ in 0..read {
+        ends.push(End::deserialise(data)?);
+    }
+
+    Ok(ends)
+}
+
+impl Serialisable for Target {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        self.check_end_times()?;
         res.append_string(&self.name)?;
 
-        res.append_u32(self.distance);
-        res.append_string(&self.distance_unit)?;
+        if res.version() < Version::V0_6_0 {
+            res.append_u32(self.distance.value);
+            res.append_string(&self.distance.unit.symbol().to_string())?;
 
-        res.append_u32(self.face_size);
-        res.append_string(&self.face_size_unit)?;
+            res.append_u32(self.face_size.value);
+            res.append_string(&self.face_size.unit.symbol().to_string())?;
+        } else {
+            self.distance.serialise_into(res)?;
+            self.face_size.serialise_into(res)?;
+        }
 
         res.append_u32(self.inclination);
 
-        res.append_usize(self.ends.len())?;
-        for end in &self.ends {
-            res.append(&mut end.serialise()?);
+        if res.version() < Version::V0_4_0 {
+            // unknown ends cannot be framed before 0.4.0, so they are left out
+            let ends: Vec<&End> = self.ends.iter()
+                .filter(|end| !matches!(end, End::Unknown { .. }))
+                .collect();
+            serialise_ends(&ends, res)?;
+        } else {
+            if let Some(face) = &self.face {
+                res.append_chunk(FACE_CHUNK, |res| face.serialise_into(res))?;
+            }
+
+            if !self.ends.is_empty() {
+                let ends: Vec<&End> = self.ends.iter().collect();
+                res.append_chunk(ENDS_CHUNK, |res| serialise_ends(&ends, res))?;
+            }
+
+            if !self.end_times.is_empty() {
+                res.append_chunk(END_TIMES_CHUNK, |res| self.end_times.serialise_into(res))?;
+            }
         }
 
-        Ok(res)
+        Ok(())
     }
 
-    fn deserialise(data: &mut Buffer) -> Result<Self, BufferError> {
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
         let name = data.pop_string()?;
 
-        let dist = data.pop_u32()?;
-        let dist_unit = data.pop_string()?;
+        let (distance, face_size) = if data.version() < Version::V0_6_0 {
+            let distance = data.pop_u32()?;
+            let distance_unit = LengthUnit::deserialise_legacy(LengthUnit::Metres, data)?;
 
-        let face = data.pop_u32()?;
-        let face_unit = data.pop_string()?;
+            let face_size = data.pop_u32()?;
+            let face_size_unit = LengthUnit::deserialise_legacy(LengthUnit::Centimetres, data)?;
+
+            (Length::new(distance, distance_unit), Length::new(face_size, face_size_unit))
+        } else {
+            (Length::deserialise(data)?, Length::deserialise(data)?)
+        };
 
         let inclination = data.pop_u32()?;
 
+        let mut face = None;
         let mut ends = vec![];
-        let read = data.pop_usize()?;
-
-        for _ in 0..read {
-            ends.push(End::deserialise(data)?);
+        let mut end_times = vec![];
+
+        if data.version() < Version::V0_4_0 {
+            ends = deserialise_ends(data)?;
+        } else {
+            while data.remaining() > 0 {
+                let (tag, mut chunk) = data.pop_chunk()?;
+                if tag == ENDS_CHUNK {
+                    ends.append(&mut deserialise_ends(&mut chunk)?);
+                } else if tag == FACE_CHUNK {
+                    face = Some(FaceKind::deserialise(&mut chunk)?);
+                } else if tag == END_TIMES_CHUNK {
+                    end_times = Vec::deserialise(&mut chunk)?;
+                }
+            }
         }
 
-        Ok(Target {
+        let target = Target {
             name,
-            distance: dist,
-            distance_unit: dist_unit,
-            face_size: face,
-            face_size_unit: face_unit,
+            distance,
+            face_size,
+            face,
             inclination,
             ends,
-        })
+            end_times,
+        };
+        target.check_end_times()?;
+
+        Ok(target)
     }
 }
 
@@ -227,67 +686,77 @@ pub enum End {
     ShotTrainer(u32),
     BareShaft(Vec<BareShaft>),
     BowDraws(u32),
+    /// An End of a kind this library does not know, kept by a lenient Reader so it can be written
+    /// back unchanged
+    Unknown {
+        tag: u8,
+        bytes: Vec<u8>,
+    },
 }
 
-impl Serialisable<End> for End {
-    fn serialise(&self) -> Result<Buffer, BufferError> {
-        Ok(match self {
-            End::Scored(scores) => {
-                let mut res = Buffer::from(vec![0]);
+impl End {
+    /// Returns the type tag written before the End
+    fn tag(&self) -> u8 {
+        match self {
+            End::Scored(_) => 0,
+            End::Measured(_) => 1,
+            End::Blank(_) => 2,
+            End::ShotTrainer(_) => 3,
+            End::BareShaft(_) => 4,
+            End::BowDraws(_) => 5,
+            End::Unknown { tag, .. } => *tag,
+        }
+    }
+
+    /// Returns the value of every arrow in a Scored or Measured end, and nothing for other kinds
+    pub fn score_values(&self) -> Vec<ScoreValue> {
+        match self {
+            End::Scored(scores) => scores.iter().map(|score| score.value).collect(),
+            End::Measured(scores) => scores.iter().map(|score| score.value).collect(),
+            _ => vec![],
+        }
+    }
 
+    /// Internal method for writing everything after the type tag
+    fn serialise_contents(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        match self {
+            End::Scored(scores) => {
                 res.append_usize(scores.len())?;
                 for score in scores {
-                    res.append(&mut score.serialise()?);
+                    score.serialise_into(res)?;
                 }
-
-                res
             },
             End::Measured(scores) => {
-                let mut res = Buffer::from(vec![1]);
-
                 res.append_usize(scores.len())?;
                 for score in scores {
-                    res.append(&mut score.serialise()?);
+                    score.serialise_into(res)?;
                 }
-
-                res
             },
             End::Blank(count) => {
-                let mut res = Buffer::from(vec![2]);
-
                 res.append_u32(*count);
-
-                res
             },
             End::ShotTrainer(count) => {
-                let mut res = Buffer::from(vec![3]);
-
                 res.append_u32(*count);
-
-                res
             },
             End::BareShaft(scores) => {
-                let mut res = Buffer::from(vec![4]);
-
                 res.append_usize(scores.len())?;
                 for score in scores {
-                    res.append(&mut score.serialise()?);
+                    score.serialise_into(res)?;
                 }
-
-                res
             },
             End::BowDraws(count) => {
-                let mut res = Buffer::from(vec![5]);
-
                 res.append_u32(*count);
-
-                res
             },
-        })
+            End::Unknown { bytes, .. } => {
+                res.append_bytes(bytes);
+            },
+        }
+
+        Ok(())
     }
 
-    fn deserialise(data: &mut Buffer) -> Result<Self, BufferError> {
-        let t = data.pop_u8()?;
+    /// Internal method for reading everything after the type tag `t`, found at `offset`
+    fn deserialise_contents(t: u8, offset: usize, data: &mut Reader) -> Result<Self, BufferError> {
         Ok(match t {
             0 => {
                 let mut scores = vec![];
@@ -332,241 +801,1014 @@ impl Serialisable<End> for End {
                 End::BowDraws(data.pop_u32()?)
             },
             other => {
-                panic!("unknown end type: {}", other)
+                return Err(BufferError::UnknownEndType { tag: other, offset })
             }
         })
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub struct ValueScore {
-    pub value: u8,
-    pub value_name: String,
+impl Serialisable for End {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        res.append_u8(self.tag());
+
+        if res.version() < Version::V0_4_0 {
+            self.serialise_contents(res)
+        } else {
+            // each End carries its length so readers can keep ends of kinds they do not know
+            let mut contents = Buffer::with_version(res.version());
+            self.serialise_contents(&mut contents)?;
+
+            res.append_usize(contents.length())?;
+            res.append(&mut contents);
+
+            Ok(())
+        }
+    }
+
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
+        let offset = data.position();
+        let t = data.pop_u8()?;
+
+        if data.version() < Version::V0_4_0 {
+            return End::deserialise_contents(t, offset, data);
+        }
+
+        let len = data.pop_usize()?;
+        let mut contents = data.pop_reader(len)?;
+
+        match End::deserialise_contents(t, offset, &mut contents) {
+            Err(BufferError::UnknownEndType { .. }) if data.lenient() => Ok(End::Unknown {
+                tag: t,
+                bytes: contents.rest().to_vec(),
+            }),
+            res => res,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
-pub struct MeasuredScore {
-    pub value: u8,
-    pub value_name: String,
-    pub r: u32,
-    pub theta: u32,
+/// What a single arrow scored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScoreValue {
+    /// A scoring zone worth the given points
+    Zone(u8),
+    /// An X or inner ten, worth the given points
+    X(u8),
+    Miss,
+    /// An arrow that counts as a hit but carries no points, as on some 3D and field targets
+    Hit,
 }
 
-#[derive(Debug, PartialEq)]
-pub struct BareShaft {
-    pub r: u32,
-    pub theta: u32,
+/// Encoding of the ScoreValue variants in one byte from format 0.7.0: a Zone below 0x80, an X
+/// from 0x80 with its points in the low bits, then Hit and Miss
+const SCORE_X: u8 = 0x80;
+const SCORE_HIT: u8 = 0xfe;
+const SCORE_MISS: u8 = 0xff;
+
+impl ScoreValue {
+    /// Returns the points this arrow adds to a total
+    pub fn points(&self) -> u8 {
+        match self {
+            ScoreValue::Zone(points) | ScoreValue::X(points) => *points,
+            ScoreValue::Miss | ScoreValue::Hit => 0,
+        }
+    }
+
+    pub fn is_x(&self) -> bool {
+        matches!(self, ScoreValue::X(_))
+    }
+
+    pub fn is_hit(&self) -> bool {
+        !matches!(self, ScoreValue::Miss)
+    }
+
+    /// Reads the value and free-text name written for an arrow before format 0.7.0
+    ///
+    /// Names such as "X", "10*" or "inner 10" are Xs worth the value, "M" or "miss" are misses
+    /// and "H" or "hit" are hits; otherwise a numeric name wins over the value, and anything
+    /// else is the zone of the value, or a miss if it is 0
+    pub fn parse_legacy(value: u8, name: &str) -> ScoreValue {
+        let name = name.trim().to_ascii_lowercase();
+
+        match name.as_str() {
+            "x" | "x10" | "10x" | "10*" | "10+" | "*" | "inner 10" | "inner ten" => {
+                ScoreValue::X(if value == 0 { 10 } else { value })
+            },
+            "m" | "miss" | "-" => ScoreValue::Miss,
+            "h" | "hit" => ScoreValue::Hit,
+            _ => match name.parse::<u8>() {
+                Ok(0) => ScoreValue::Miss,
+                Ok(points) => ScoreValue::Zone(points),
+                Err(_) if value == 0 => ScoreValue::Miss,
+                Err(_) => ScoreValue::Zone(value),
+            },
+        }
+    }
+}
+
+impl fmt::Display for ScoreValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScoreValue::Zone(points) => write!(f, "{}", points),
+            ScoreValue::X(_) => write!(f, "X"),
+            ScoreValue::Miss => write!(f, "M"),
+            ScoreValue::Hit => write!(f, "H"),
+        }
+    }
 }
 
-impl Serialisable<ValueScore> for ValueScore {
-    fn serialise(&self) -> Result<Buffer, BufferError> {
-        let mut res = Buffer::from(vec![self.value]);
+impl Serialisable for ScoreValue {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        if res.version() < Version::V0_7_0 {
+            res.append_u8(self.points());
+            return res.append_string(&self.to_string());
+        }
 
-        res.append_string(&self.value_name)?;
+        let b = match self {
+            ScoreValue::Zone(points) if *points < SCORE_X => *points,
+            ScoreValue::X(points) if *points < SCORE_HIT - SCORE_X => SCORE_X | *points,
+            ScoreValue::Hit => SCORE_HIT,
+            ScoreValue::Miss => SCORE_MISS,
+            _ => return Err(BufferError::ScoreTooBig(self.points())),
+        };
+        res.append_u8(b);
 
-        Ok(res)
+        Ok(())
     }
 
-    fn deserialise(data: &mut Buffer) -> Result<Self, BufferError> {
-        Ok(Self {
-            value: data.pop_u8()?,
-            value_name: data.pop_string()?,
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
+        if data.version() < Version::V0_7_0 {
+            let value = data.pop_u8()?;
+            return Ok(ScoreValue::parse_legacy(value, data.pop_str()?));
+        }
+
+        Ok(match data.pop_u8()? {
+            SCORE_MISS => ScoreValue::Miss,
+            SCORE_HIT => ScoreValue::Hit,
+            b if b >= SCORE_X => ScoreValue::X(b & !SCORE_X),
+            b => ScoreValue::Zone(b),
         })
     }
 }
 
-impl Serialisable<MeasuredScore> for MeasuredScore {
-    fn serialise(&self) -> Result<Buffer, BufferError> {
-        let mut res = Buffer::from(vec![self.value]);
+#[derive(Serialisable, Debug, Clone, PartialEq)]
+pub struct ValueScore {
+    pub value: ScoreValue,
+}
+
+/// How many steps the distance and angle of an arrow position are written in per millimetre
+/// and degree, from format 0.8.0
+pub const POSITION_SCALE: u32 = 100;
+
+/// Internal method for writing an arrow position, as whole millimetres and degrees before
+/// format 0.8.0
+fn serialise_position(r: u32, theta: u32, res: &mut Buffer) {
+    if res.version() < Version::V0_8_0 {
+        let round = |n: u32| ((n as u64 + POSITION_SCALE as u64 / 2) / POSITION_SCALE as u64) as u32;
+        res.append_u32(round(r));
+        res.append_u32(round(theta) % 360);
+    } else {
+        res.append_u32(r);
+        res.append_u32(theta);
+    }
+}
 
-        res.append_string(&self.value_name)?;
+/// Internal method for reading an arrow position, scaling the whole millimetres and degrees of
+/// files older than format 0.8.0
+fn deserialise_position(data: &mut Reader) -> Result<(u32, u32), BufferError> {
+    let (r, theta) = (data.pop_u32()?, data.pop_u32()?);
+
+    if data.version() < Version::V0_8_0 {
+        Ok((r.saturating_mul(POSITION_SCALE), theta % 360 * POSITION_SCALE))
+    } else {
+        Ok((r, theta))
+    }
+}
 
-        res.append_u32(self.r);
-        res.append_u32(self.theta);
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasuredScore {
+    pub value: ScoreValue,
+    /// Distance of the centre of the arrow from the centre of the face, in hundredths of a
+    /// millimetre
+    pub r: u32,
+    /// Angle of the arrow anticlockwise from the right of the face, in hundredths of a degree
+    pub theta: u32,
+}
+
+impl MeasuredScore {
+    /// Returns the position of the arrow in millimetres right of and above the centre of the
+    /// face
+    pub fn position_mm(&self) -> (f64, f64) {
+        to_cartesian(self.r, self.theta)
+    }
+}
 
-        Ok(res)
+impl Serialisable for MeasuredScore {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        self.value.serialise_into(res)?;
+        serialise_position(self.r, self.theta, res);
+        Ok(())
     }
 
-    fn deserialise(data: &mut Buffer) -> Result<Self, BufferError> {
-        Ok(Self {
-            value: data.pop_u8()?,
-            value_name: data.pop_string()?,
-            r: data.pop_u32()?,
-            theta: data.pop_u32()?,
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
+        let value = ScoreValue::deserialise(data)?;
+        let (r, theta) = deserialise_position(data)?;
+
+        Ok(MeasuredScore {
+            value,
+            r,
+            theta,
         })
     }
 }
 
-impl Serialisable<BareShaft> for BareShaft {
-    fn serialise(&self) -> Result<Buffer, BufferError> {
-        let mut res = Buffer::from(vec![]);
+/// Converts a position in hundredths of a millimetre and of a degree, as in MeasuredScore and
+/// BareShaft, to millimetres right of and above the centre
+pub fn to_cartesian(r: u32, theta: u32) -> (f64, f64) {
+    let scale = POSITION_SCALE as f64;
+    let (sin, cos) = (theta as f64 / scale).to_radians().sin_cos();
+    (r as f64 / scale * cos, r as f64 / scale * sin)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BareShaft {
+    /// Distance from the centre of the face in hundredths of a millimetre, as in MeasuredScore
+    pub r: u32,
+    /// Angle anticlockwise from the right of the face in hundredths of a degree, as in
+    /// MeasuredScore
+    pub theta: u32,
+}
 
-        res.append_u32(self.r);
-        res.append_u32(self.theta);
+impl BareShaft {
+    /// Returns the position of the shaft in millimetres right of and above the centre of the
+    /// face
+    pub fn position_mm(&self) -> (f64, f64) {
+        to_cartesian(self.r, self.theta)
+    }
+}
 
-        Ok(res)
+impl Serialisable for BareShaft {
+    fn serialise_into(&self, res: &mut Buffer) -> Result<(), BufferError> {
+        serialise_position(self.r, self.theta, res);
+        Ok(())
     }
 
-    fn deserialise(data: &mut Buffer) -> Result<Self, BufferError> {
-        Ok(Self {
-            r: data.pop_u32()?,
-            theta: data.pop_u32()?,
+    fn deserialise(data: &mut Reader) -> Result<Self, BufferError> {
+        let (r, theta) = deserialise_position(data)?;
+
+        Ok(BareShaft {
+            r,
+            theta,
         })
     }
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::archer::GenderCategory;
+    use crate::date::{Date, UtcOffset};
+    use crate::bow::BowStyle;
 
     #[test]
     fn test_files() {
         let s = Session {
-            date: "2024-01-02".to_string(),
-            location: "Home".to_string(),
+            date: Some(SessionDate::new(Date::new(2024, 1, 2).unwrap())),
             rounds: vec![
                 Round {
                     name: "Portsmouth".to_string(),
                     targets: vec![
                         Target {
-                            name: "WA 60cm Indoor".to_string(),
-                            distance: 18,
-                            distance_unit: "m".to_string(),
-                            face_size: 60,
-                            face_size_unit: "cm".to_string(),
-                            inclination: 0,
                             ends: vec![
                                 End::Scored(vec![
                                     ValueScore {
-                                        value: 10,
-                                        value_name: "ten".to_string(),
+                                        value: ScoreValue::Zone(10),
                                     },
                                     ValueScore {
-                                        value: 10,
-                                        value_name: "ten".to_string(),
+                                        value: ScoreValue::Zone(10),
                                     },
                                     ValueScore {
-                                        value: 10,
-                                        value_name: "ten".to_string(),
+                                        value: ScoreValue::Zone(10),
                                     },
                                 ])
                             ],
+                            ..portsmouth_target()
                         }
                     ],
                 }
             ],
+            ..Session::new("Home".to_string())
         };
 
-        s.encode("tmp.oaf".to_string()).unwrap();
+        s.encode("tmp.oaf").unwrap();
 
-        let decoded_s = Session::decode("tmp.oaf".to_string()).unwrap();
+        let decoded_s = Session::decode("tmp.oaf").unwrap();
 
         assert_eq!(s, decoded_s)
     }
 
+    #[test]
+    fn test_bytes() {
+        let s = simple_session();
+
+        assert_eq!(s, Session::from_bytes(&s.to_bytes().unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_stream() {
+        let s = simple_session();
+
+        let mut stream = vec![];
+        s.write_to(&mut stream).unwrap();
+        stream.extend_from_slice(b"next");
+
+        let mut reader = io::Cursor::new(stream);
+        assert_eq!(s, Session::read_from(&mut reader).unwrap());
+
+        let mut rest = vec![];
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(b"next".to_vec(), rest);
+    }
+
+    #[test]
+    fn test_stream_truncated() {
+        let mut data = simple_session().to_bytes().unwrap();
+        data.pop();
+
+        let res = Session::read_from(data.as_slice());
+        assert!(matches!(res, Err(FileError::LengthMismatch { .. })));
+    }
+
+    #[test]
+    fn test_stream_hostile_length() {
+        let mut data = simple_session().to_bytes().unwrap();
+        data[HEADER_LENGTH - 8..HEADER_LENGTH].copy_from_slice(&u64::MAX.to_le_bytes());
+
+        let res = Session::read_from(data.as_slice());
+        assert!(matches!(res, Err(FileError::LengthMismatch { expected: u64::MAX, .. })));
+
+        let res = Session::from_bytes(&data);
+        assert!(matches!(res, Err(FileError::LengthMismatch { expected: u64::MAX, .. })));
+    }
+
+    fn simple_session() -> Session {
+        Session {
+            date: Some(SessionDate::new(Date::new(2024, 1, 2).unwrap())),
+            ..Session::new("Home".to_string())
+        }
+    }
+
+    fn portsmouth_target() -> Target {
+        Target::new(
+            "WA 60cm Indoor".to_string(),
+            Length::new(18, LengthUnit::Metres),
+            Length::new(60, LengthUnit::Centimetres),
+        )
+    }
+
+    /// Encodes a simple session, lets the caller corrupt the bytes, and decodes the result
+    fn decode_tampered(tamper: impl FnOnce(&mut Vec<u8>)) -> Result<Session, FileError> {
+        let mut data = simple_session().to_bytes().unwrap();
+        tamper(&mut data);
+
+        Session::from_bytes(&data)
+    }
+
+    #[test]
+    fn test_decode_bad_magic() {
+        let res = decode_tampered(|data| data[0] = b'X');
+        assert!(matches!(res, Err(FileError::BadMagic)));
+
+        let res = decode_tampered(|data| data.truncate(2));
+        assert!(matches!(res, Err(FileError::BadMagic)));
+    }
+
+    #[test]
+    fn test_decode_newer_version() {
+        let res = decode_tampered(|data| data[5] = 9);
+        assert!(matches!(res, Err(FileError::UnsupportedVersion { major: 0, minor: 9, patch: 0 })));
+    }
+
+    #[test]
+    fn test_decode_checksum_mismatch() {
+        let mut s = simple_session();
+        let mut data = s.to_bytes().unwrap();
+        // flip a bit in the last letter of the location
+        let offset = data.windows(4).position(|w| w == b"Home").unwrap() + 3;
+        data[offset] ^= 0x01;
+
+        let res = Session::from_bytes(&data);
+        assert!(matches!(res, Err(FileError::ChecksumMismatch { .. })));
+
+        let options = DecodeOptions {
+            verify_checksum: false,
+            ..Default::default()
+        };
+        s.location = "Homd".to_string();
+        assert_eq!(s, Session::from_bytes_with(&data, &options).unwrap());
+    }
+
+    #[test]
+    fn test_decode_checksum_footer() {
+        let data = simple_session().to_bytes().unwrap();
+        let body = &data[HEADER_LENGTH..data.len() - 4];
+
+        assert_eq!(&crc32c(body).to_le_bytes(), &data[data.len() - 4..]);
+    }
+
+    #[test]
+    fn test_decode_length_mismatch() {
+        let res = decode_tampered(|data| { data.pop(); });
+        assert!(matches!(res, Err(FileError::LengthMismatch { .. })));
+
+        let res = decode_tampered(|data| data.push(0));
+        assert!(matches!(res, Err(FileError::LengthMismatch { .. })));
+    }
+
+    #[test]
+    fn test_unknown_end_type() {
+        let res = End::deserialise(&mut Reader::new(&[2, 4, 7, 0, 0, 0]));
+        assert_eq!(Ok(End::Blank(7)), res);
+
+        let res = End::deserialise(&mut Reader::new(&[9, 4, 7, 0, 0, 0]));
+        assert!(matches!(res, Err(BufferError::UnknownEndType { tag: 9, offset: 0 })));
+    }
+
+    #[test]
+    fn test_unknown_end_type_offset() {
+        let mut s = simple_session();
+        s.rounds.push(Round {
+            name: "Portsmouth".to_string(),
+            targets: vec![
+                Target {
+                    ends: vec![End::BowDraws(20)],
+                    ..portsmouth_target()
+                }
+            ],
+        });
+
+        let mut data = s.to_bytes().unwrap();
+        // the BowDraws end is the last thing in the body: a tag, its length and a u32
+        let offset = data.len() - 4 - 6;
+        data[offset] = 200;
+
+        let options = DecodeOptions {
+            verify_checksum: false,
+            ..Default::default()
+        };
+        let res = Session::from_bytes_with(&data, &options);
+        assert!(matches!(
+            res,
+            Err(FileError::BufferError(BufferError::UnknownEndType { tag: 200, offset: o })) if o == offset
+        ));
+    }
+
+    #[test]
+    fn test_decode_v0_1_0() {
+        let data = vec![
+            0x4f, 0x41, 0x46, 0x46, 0, 1, 0, 14, 0, 0, 0, 0, 0, 0, 0,
+            4, 0, 50, 48, 50, 52, 4, 0, 72, 111, 109, 101, 0, 0,
+        ];
+        let s = Session {
+            legacy_date: Some("2024".to_string()),
+            ..Session::new("Home".to_string())
+        };
+
+        assert_eq!(s, Session::from_bytes(&data).unwrap())
+    }
+
+    #[test]
+    fn test_encode_v0_1_0() {
+        let s = Session {
+            legacy_date: Some("2024".to_string()),
+            ..Session::new("Home".to_string())
+        };
+        let options = EncodeOptions {
+            version: Version::V0_1_0,
+        };
+        let data = vec![
+            0x4f, 0x41, 0x46, 0x46, 0, 1, 0, 14, 0, 0, 0, 0, 0, 0, 0,
+            4, 0, 50, 48, 50, 52, 4, 0, 72, 111, 109, 101, 0, 0,
+        ];
+
+        assert_eq!(data, s.to_bytes_with(&options).unwrap());
+    }
+
+    #[test]
+    fn test_encode_unsupported_version() {
+        let options = EncodeOptions {
+            version: Version::new(0, 1, 5),
+        };
+
+        let res = simple_session().to_bytes_with(&options);
+        assert!(matches!(res, Err(FileError::UnsupportedVersion { major: 0, minor: 1, patch: 5 })));
+
+        let res = decode_tampered(|data| data[6] = 5);
+        assert!(matches!(res, Err(FileError::UnsupportedVersion { major: 0, patch: 5, .. })));
+    }
+
+    #[test]
+    fn test_round_trip_every_version() {
+        let s = Session {
+            date: Some(SessionDate::new(Date::new(2023, 12, 4).unwrap())),
+            rounds: vec![
+                Round {
+                    name: "Portsmouth".to_string(),
+                    targets: vec![
+                        Target {
+                            ends: vec![
+                                End::Measured(vec![
+                                    MeasuredScore {
+                                        value: ScoreValue::Zone(9),
+                                        r: 4000,
+                                        theta: 9000,
+                                    },
+                                ]),
+                                End::BareShaft(vec![
+                                    BareShaft {
+                                        r: 1200,
+                                        theta: 18000,
+                                    },
+                                ]),
+                                End::Blank(30),
+                            ],
+                            ..portsmouth_target()
+                        }
+                    ],
+                }
+            ],
+            ..Session::new("St Andrews".to_string())
+        };
+
+        for version in Version::SUPPORTED {
+            let options = EncodeOptions {
+                version: *version,
+            };
+            let data = s.to_bytes_with(&options).unwrap();
+
+            assert_eq!(&data[4..7], &[version.major, version.minor, version.patch]);
+            assert_eq!(s, Session::from_bytes(&data).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_long_strings() {
+        let mut s = simple_session();
+        s.location = "x".repeat(100_000);
+
+        assert_eq!(s, Session::from_bytes(&s.to_bytes().unwrap()).unwrap())
+    }
+
+    /// Returns the body of a chunked file holding the given Session chunk contents, with the
+    /// header and checksum filled in
+    fn chunked_file(f: impl FnOnce(&mut Buffer) -> Result<(), BufferError>) -> Vec<u8> {
+        let mut body = Buffer::new();
+        body.append_chunk(SESSION_CHUNK, f).unwrap();
+
+        let mut res = Buffer::new();
+        res.append_bytes(&MAGIC);
+        res.append_u8(Version::CURRENT.major);
+        res.append_u8(Version::CURRENT.minor);
+        res.append_u8(Version::CURRENT.patch);
+        res.append_u64(body.length() as u64);
+        let checksum = crc32c(body.bytes());
+        res.append(&mut body);
+        res.append_u32(checksum);
+
+        res.take_underlying_buffer()
+    }
+
+    #[test]
+    fn test_skip_unknown_chunks() {
+        let data = chunked_file(|res| {
+            simple_session().date.serialise_into(res)?;
+            res.append_u8(0);
+            res.append_string(&"Home".to_string())?;
+            res.append_chunk(*b"XTRA", |res| {
+                res.append_u32(42);
+                Ok(())
+            })?;
+            res.append_chunk(ROUND_CHUNK, |res| {
+                res.append_string(&"Portsmouth".to_string())?;
+                res.append_chunk(*b"XTRA", |_| Ok(()))
+            })
+        });
+
+        let mut s = simple_session();
+        s.rounds.push(Round {
+            name: "Portsmouth".to_string(),
+            targets: vec![],
+        });
+
+        assert_eq!(s, Session::from_bytes(&data).unwrap());
+    }
+
+    #[test]
+    fn test_missing_session_chunk() {
+        let mut data = simple_session().to_bytes().unwrap();
+        data[HEADER_LENGTH..HEADER_LENGTH + 4].copy_from_slice(b"XTRA");
+        let options = DecodeOptions {
+            verify_checksum: false,
+            ..Default::default()
+        };
+
+        let res = Session::from_bytes_with(&data, &options);
+        assert!(matches!(res, Err(FileError::BufferError(BufferError::MissingChunk(SESSION_CHUNK)))));
+    }
+
+    #[test]
+    fn test_bow() {
+        let mut s = simple_session();
+        s.bow = Some(Bow {
+            draw_weight: Some(40.0),
+            limbs: Some("Uukha VX1000".to_string()),
+            ..Bow::new(BowStyle::Recurve)
+        });
+
+        assert_eq!(s, Session::from_bytes(&s.to_bytes().unwrap()).unwrap());
+
+        // older versions cannot hold the bow, so it is left out
+        let options = EncodeOptions {
+            version: Version::V0_3_0,
+        };
+        let decoded = Session::from_bytes(&s.to_bytes_with(&options).unwrap()).unwrap();
+        assert_eq!(None, decoded.bow);
+    }
+
+    #[test]
+    fn test_archer() {
+        let mut s = simple_session();
+        s.archer = Some(Archer {
+            membership_number: Some("1234567".to_string()),
+            gender: Some(GenderCategory::Female),
+            ..Archer::new("Robin".to_string())
+        });
+
+        assert_eq!(s, Session::from_bytes(&s.to_bytes().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_legacy_dates() {
+        let options = EncodeOptions {
+            version: Version::V0_4_0,
+        };
+        let mut s = simple_session();
+        s.date = None;
+
+        s.legacy_date = Some("4/12/2023".to_string());
+        let decoded = Session::from_bytes(&s.to_bytes_with(&options).unwrap()).unwrap();
+        assert_eq!(Some(SessionDate::new(Date::new(2023, 12, 4).unwrap())), decoded.date);
+        assert_eq!(None, decoded.legacy_date);
+
+        // a date that cannot be interpreted is kept as it was written
+        s.legacy_date = Some("the first sunny day".to_string());
+        let decoded = Session::from_bytes(&s.to_bytes_with(&options).unwrap()).unwrap();
+        assert_eq!(s, decoded);
+        assert_eq!(s, Session::from_bytes(&decoded.to_bytes().unwrap()).unwrap());
+
+        // times survive being written as text
+        s.legacy_date = None;
+        s.date = Some(SessionDate {
+            start: Some(Time::new(9, 30, 0).unwrap()),
+            utc_offset: Some(UtcOffset::from_minutes(60).unwrap()),
+            ..SessionDate::new(Date::new(2024, 1, 2).unwrap())
+        });
+        assert_eq!(s, Session::from_bytes(&s.to_bytes_with(&options).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_legacy_units() {
+        let mut buf = Buffer::with_version(Version::V0_5_0);
+        buf.append_string(&"Bray I".to_string()).unwrap();
+        buf.append_u32(20);
+        buf.append_string(&"Yds".to_string()).unwrap();
+        buf.append_u32(40);
+        buf.append_string(&"".to_string()).unwrap();
+        buf.append_u32(0);
+
+        let target = Target::deserialise(&mut buf.reader()).unwrap();
+        assert_eq!(Length::new(20, LengthUnit::Yards), target.distance);
+        assert_eq!(Length::new(40, LengthUnit::Centimetres), target.face_size);
+    }
+
+    #[test]
+    fn test_legacy_unknown_units() {
+        let options = EncodeOptions {
+            version: Version::V0_5_0,
+        };
+        let mut s = simple_session();
+        s.rounds.push(Round {
+            name: "Bray I".to_string(),
+            targets: vec![
+                Target::new(
+                    "WA 40cm".to_string(),
+                    Length::new(20, LengthUnit::Yards),
+                    Length::new(40, LengthUnit::Centimetres),
+                ),
+            ],
+        });
+
+        // any string was allowed as a unit before 0.6.0, so write ones this library cannot read
+        let mut data = s.to_bytes_with(&options).unwrap();
+        for (from, to) in [(b"\x02yd", b"\x02??"), (b"\x02cm", b"\x02zz")] {
+            let offset = data.windows(3).position(|w| w == from).unwrap();
+            data[offset..offset + 3].copy_from_slice(to);
+        }
+        let footer = data.len() - 4;
+        let checksum = crc32c(&data[HEADER_LENGTH..footer]);
+        data[footer..].copy_from_slice(&checksum.to_le_bytes());
+
+        let target = &Session::from_bytes(&data).unwrap().rounds[0].targets[0];
+        assert_eq!(Length::new(20, LengthUnit::Metres), target.distance);
+        assert_eq!(Length::new(40, LengthUnit::Centimetres), target.face_size);
+    }
+
+    #[test]
+    fn test_legacy_positions() {
+        let mut s = simple_session();
+        s.rounds.push(Round {
+            name: "Portsmouth".to_string(),
+            targets: vec![
+                Target {
+                    ends: vec![
+                        End::Measured(vec![MeasuredScore { value: ScoreValue::Zone(9), r: 4049, theta: 35_960 }]),
+                        End::BareShaft(vec![BareShaft { r: 1250, theta: 4525 }]),
+                    ],
+                    ..portsmouth_target()
+                }
+            ],
+        });
+        assert_eq!(s, Session::from_bytes(&s.to_bytes().unwrap()).unwrap());
+
+        // older versions hold whole millimetres and degrees, which are scaled back up when read
+        let options = EncodeOptions {
+            version: Version::V0_7_0,
+        };
+        let decoded = Session::from_bytes(&s.to_bytes_with(&options).unwrap()).unwrap();
+        assert_eq!(
+            End::Measured(vec![MeasuredScore { value: ScoreValue::Zone(9), r: 4000, theta: 0 }]),
+            decoded.rounds[0].targets[0].ends[0]
+        );
+        assert_eq!(End::BareShaft(vec![BareShaft { r: 1300, theta: 4500 }]), decoded.rounds[0].targets[0].ends[1]);
+    }
+
+    #[test]
+    fn test_face() {
+        let mut s = simple_session();
+        s.rounds.push(Round {
+            name: "Portsmouth".to_string(),
+            targets: vec![
+                Target {
+                    face: Some(FaceKind::WaTenZone),
+                    ..portsmouth_target()
+                }
+            ],
+        });
+
+        let decoded = Session::from_bytes(&s.to_bytes().unwrap()).unwrap();
+        assert_eq!(s, decoded);
+
+        let face = decoded.rounds[0].targets[0].face_geometry().unwrap();
+        assert_eq!(Some(9), face.ring_at(0.0, 50.0).map(|ring| ring.value));
+    }
+
+    #[test]
+    fn test_end_times() {
+        let mut s = simple_session();
+        s.rounds.push(Round {
+            name: "Portsmouth".to_string(),
+            targets: vec![
+                Target {
+                    ends: vec![End::Blank(6), End::BowDraws(20)],
+                    end_times: vec![Some(Time::new(9, 30, 0).unwrap()), None],
+                    ..portsmouth_target()
+                }
+            ],
+        });
+
+        let decoded = Session::from_bytes(&s.to_bytes().unwrap()).unwrap();
+        assert_eq!(s, decoded);
+        assert_eq!(Some(Time::new(9, 30, 0).unwrap()), decoded.rounds[0].targets[0].end_time(0));
+        assert_eq!(None, decoded.rounds[0].targets[0].end_time(1));
+        assert_eq!(None, decoded.rounds[0].targets[0].end_time(2));
+
+        // times recorded as unknown are kept as they were
+        s.rounds[0].targets[0].end_times = vec![None, None];
+        assert_eq!(s, Session::from_bytes(&s.to_bytes().unwrap()).unwrap());
+
+        s.rounds[0].targets[0].end_times.pop();
+        let res = s.to_bytes();
+        assert!(matches!(res, Err(FileError::BufferError(BufferError::EndTimesMismatch { ends: 2, times: 1 }))));
+    }
+
+    #[test]
+    fn test_push_end() {
+        let mut target = portsmouth_target();
+        target.push_end(End::Blank(6), None);
+        assert!(target.end_times.is_empty());
+
+        target.push_end(End::Blank(6), Some(Time::new(9, 30, 0).unwrap()));
+        target.push_end(End::Blank(6), None);
+        assert_eq!(vec![None, Some(Time::new(9, 30, 0).unwrap()), None], target.end_times);
+        assert_eq!(3, target.ends.len());
+    }
+
+    #[test]
+    fn test_extensions() {
+        let mut s = simple_session();
+        s.extensions.push(Extension::new(*b"wthr", &"gusty".to_string()).unwrap());
+        s.extensions.push(Extension::new(*b"temp", &18u32).unwrap());
+
+        let decoded = Session::from_bytes(&s.to_bytes().unwrap()).unwrap();
+        assert_eq!(s, decoded);
+        assert_eq!("gusty", decoded.extensions[0].read::<String>().unwrap());
+        assert_eq!(18, decoded.extensions[1].read::<u32>().unwrap());
+
+        // older versions cannot hold extensions, so they are left out
+        let options = EncodeOptions {
+            version: Version::V0_3_0,
+        };
+        let decoded = Session::from_bytes(&s.to_bytes_with(&options).unwrap()).unwrap();
+        assert_eq!(simple_session(), decoded);
+    }
+
+    #[test]
+    fn test_extension_reserved_tag() {
+        assert_eq!(Err(BufferError::ReservedTag(*b"ROND")), Extension::new(*b"ROND", &0u8));
+
+        let mut s = simple_session();
+        s.extensions.push(Extension {
+            tag: *b"ROND",
+            bytes: vec![],
+        });
+        assert!(matches!(s.to_bytes(), Err(FileError::BufferError(BufferError::ReservedTag(_)))));
+    }
+
+    #[test]
+    fn test_keep_unknown_ends() {
+        let unknown = End::Unknown {
+            tag: 42,
+            bytes: vec![1, 2, 3],
+        };
+        let mut s = simple_session();
+        s.rounds.push(Round {
+            name: "Portsmouth".to_string(),
+            targets: vec![
+                Target {
+                    ends: vec![End::Blank(6), unknown, End::BowDraws(20)],
+                    ..portsmouth_target()
+                }
+            ],
+        });
+        let data = s.to_bytes().unwrap();
+
+        let res = Session::from_bytes(&data);
+        assert!(matches!(res, Err(FileError::BufferError(BufferError::UnknownEndType { tag: 42, .. }))));
+
+        let options = DecodeOptions {
+            keep_unknown: true,
+            ..Default::default()
+        };
+        let decoded = Session::from_bytes_with(&data, &options).unwrap();
+        assert_eq!(s, decoded);
+        assert_eq!(data, decoded.to_bytes().unwrap());
+
+        // older versions cannot frame the unknown end, so it is left out
+        let options = EncodeOptions {
+            version: Version::V0_3_0,
+        };
+        let decoded = Session::from_bytes(&s.to_bytes_with(&options).unwrap()).unwrap();
+        assert_eq!(vec![End::Blank(6), End::BowDraws(20)], decoded.rounds[0].targets[0].ends);
+    }
+
     #[test]
     fn test_full() {
         let s = Session {
-            date: "4/12/2023".to_string(),
-            location: "St Andrews".to_string(),
+            date: Some(SessionDate::new(Date::new(2023, 12, 4).unwrap())),
             rounds: vec![
                 Round {
                     name: "Portsmouth".to_string(),
                     targets: vec![
                         Target {
-                            name: "WA 60cm Indoor".to_string(),
-                            distance: 18,
-                            distance_unit: "m".to_string(),
-                            face_size: 60,
-                            face_size_unit: "cm".to_string(),
-                            inclination: 0,
                             ends: vec![
                                 End::Scored(vec![
                                     ValueScore {
-                                        value: 10,
-                                        value_name: "10".to_string(),
+                                        value: ScoreValue::Zone(10),
                                     },
                                     ValueScore {
-                                        value: 10,
-                                        value_name: "10".to_string(),
+                                        value: ScoreValue::Zone(10),
                                     },
                                     ValueScore {
-                                        value: 9,
-                                        value_name: "9".to_string(),
+                                        value: ScoreValue::Zone(9),
                                     }
                                 ]),
                                 End::Scored(vec![
                                     ValueScore {
-                                        value: 10,
-                                        value_name: "10".to_string(),
+                                        value: ScoreValue::Zone(10),
                                     },
                                     ValueScore {
-                                        value: 9,
-                                        value_name: "9".to_string(),
+                                        value: ScoreValue::Zone(9),
                                     },
                                     ValueScore {
-                                        value: 9,
-                                        value_name: "9".to_string(),
+                                        value: ScoreValue::Zone(9),
                                     }
                                 ])
                             ],
+                            ..portsmouth_target()
                         }
                     ],
                 }
             ],
+            ..Session::new("St Andrews".to_string())
         };
 
-        assert_eq!(s, Session::deserialise(&mut s.serialise().unwrap()).unwrap())
+        assert_eq!(s, Session::deserialise(&mut s.serialise().unwrap().reader()).unwrap())
+    }
+
+    #[test]
+    fn test_score_value_parse_legacy() {
+        assert_eq!(ScoreValue::X(10), ScoreValue::parse_legacy(10, "X"));
+        assert_eq!(ScoreValue::X(10), ScoreValue::parse_legacy(10, "10*"));
+        assert_eq!(ScoreValue::X(6), ScoreValue::parse_legacy(6, "x"));
+        assert_eq!(ScoreValue::Miss, ScoreValue::parse_legacy(0, "M"));
+        assert_eq!(ScoreValue::Miss, ScoreValue::parse_legacy(0, "miss"));
+        assert_eq!(ScoreValue::Miss, ScoreValue::parse_legacy(0, "0"));
+        assert_eq!(ScoreValue::Hit, ScoreValue::parse_legacy(0, "hit"));
+        assert_eq!(ScoreValue::Zone(9), ScoreValue::parse_legacy(0, "9"));
+        assert_eq!(ScoreValue::Zone(7), ScoreValue::parse_legacy(7, "seven"));
+    }
+
+    #[test]
+    fn test_score_value_serialise() {
+        for (value, b) in [
+            (ScoreValue::Zone(9), 9),
+            (ScoreValue::X(10), 0x8a),
+            (ScoreValue::Hit, 0xfe),
+            (ScoreValue::Miss, 0xff),
+        ] {
+            let data = value.serialise().unwrap();
+            assert_eq!(data, vec![b]);
+            assert_eq!(value, ScoreValue::deserialise(&mut data.reader()).unwrap());
+        }
+
+        assert_eq!(Err(BufferError::ScoreTooBig(200)), ScoreValue::Zone(200).serialise());
+
+        // written as points and a name before 0.7.0
+        let mut buf = Buffer::with_version(Version::V0_6_0);
+        ScoreValue::X(10).serialise_into(&mut buf).unwrap();
+        assert_eq!(buf, vec![10, 1, b'X']);
+        assert_eq!(ScoreValue::X(10), ScoreValue::deserialise(&mut buf.reader()).unwrap());
     }
 
     #[test]
     fn test_measured_score_serialise() {
         let data = MeasuredScore {
-            value: 7,
-            value_name: "seven".to_string(),
+            value: ScoreValue::Zone(7),
             r: 255,
             theta: 6000,
         }.serialise().unwrap();
         assert_eq!(
             data,
-            vec![7, 5, 0, 115, 101, 118, 101, 110, 255, 0, 0, 0, 112, 23, 0, 0]
+            vec![7, 255, 0, 0, 0, 112, 23, 0, 0]
         )
     }
 
     #[test]
     fn test_measured_score_deserialise() {
-        let data = MeasuredScore::deserialise(&mut Buffer::from(vec![7, 5, 0, 115, 101, 118, 101, 110, 255, 0, 0, 0, 112, 23, 0, 0])).unwrap();
+        // 40 mm at 90 degrees, scaled to hundredths as read from an older version
+        let data = MeasuredScore::deserialise(&mut Reader::with_version(&[7, 5, 0, 115, 101, 118, 101, 110, 40, 0, 0, 0, 90, 0, 0, 0], Version::V0_1_0)).unwrap();
         let s = MeasuredScore {
-            value: 7,
-            value_name: "seven".to_string(),
-            r: 255,
-            theta: 6000,
+            value: ScoreValue::Zone(7),
+            r: 4000,
+            theta: 9000,
         };
         assert_eq!(
             data,
             s,
-        )
+        );
+
+        let data = BareShaft::deserialise(&mut Reader::with_version(&[40, 0, 0, 0, 194, 1, 0, 0], Version::V0_7_0)).unwrap();
+        assert_eq!(BareShaft { r: 4000, theta: 9000 }, data);
+
+        let data = BareShaft::deserialise(&mut Reader::new(&[40, 0, 0, 0, 194, 1, 0, 0])).unwrap();
+        assert_eq!(BareShaft { r: 40, theta: 450 }, data);
     }
 
     #[test]
     fn test_value_score_serialise() {
         let data = ValueScore {
-            value: 7,
-            value_name: "seven".to_string(),
+            value: ScoreValue::Zone(7),
         }.serialise().unwrap();
         assert_eq!(
             data,
-            vec![7, 5, 0, 115, 101, 118, 101, 110]
+            vec![7]
         )
     }
 
     #[test]
     fn test_value_score_deserialise() {
-        let data = ValueScore::deserialise(&mut Buffer::from(vec![7, 5, 0, 115, 101, 118, 101, 110])).unwrap();
+        let data = ValueScore::deserialise(&mut Reader::with_version(&[7, 5, 0, 115, 101, 118, 101, 110], Version::V0_1_0)).unwrap();
         let s = ValueScore {
-            value: 7,
-            value_name: "seven".to_string(),
+            value: ScoreValue::Zone(7),
         };
         assert_eq!(
             data,
@@ -578,30 +1820,27 @@ mod tests {
     fn test_measured_end_serialise() {
         let data = End::Measured( vec![
             MeasuredScore {
-                value: 7,
-                value_name: "seven".to_string(),
+                value: ScoreValue::Zone(7),
                 r: 255,
                 theta: 6000,
             },
             MeasuredScore {
-                value: 6,
-                value_name: "six".to_string(),
+                value: ScoreValue::Zone(6),
                 r: 1000,
                 theta: 3000,
             },
             MeasuredScore {
-                value: 5,
-                value_name: "five".to_string(),
+                value: ScoreValue::Zone(5),
                 r: 1500,
                 theta: 50,
             }
         ] ).serialise().unwrap();
         assert_eq!(
             data,
-            vec![1, 3, 0,
-                7, 5, 0, 115, 101, 118, 101, 110, 255, 0, 0, 0, 112, 23, 0, 0,
-                6, 3, 0, 115, 105, 120, 232, 3, 0, 0, 184, 11, 0, 0,
-                5, 4, 0, 102, 105, 118, 101, 220, 5, 0, 0, 50, 0, 0, 0,
+            vec![1, 28, 3,
+                7, 255, 0, 0, 0, 112, 23, 0, 0,
+                6, 232, 3, 0, 0, 184, 11, 0, 0,
+                5, 220, 5, 0, 0, 50, 0, 0, 0,
             ]
         )
     }
@@ -609,31 +1848,30 @@ mod tests {
     #[test]
     fn test_measured_end_deserialise() {
         let data = End::deserialise(
-            &mut Buffer::from(
-                vec![1, 3, 0,
+            &mut Reader::with_version(
+                &[1, 3, 0,
                         7, 5, 0, 115, 101, 118, 101, 110, 255, 0, 0, 0, 112, 23, 0, 0,
                         6, 3, 0, 115, 105, 120, 232, 3, 0, 0, 184, 11, 0, 0,
                         5, 4, 0, 102, 105, 118, 101, 220, 5, 0, 0, 50, 0, 0, 0,
-                ]
+                ],
+                Version::V0_1_0,
             )).unwrap();
+        // whole millimetres and degrees, with angles past a full turn brought back within one
         let s = End::Measured( vec![
             MeasuredScore {
-                value: 7,
-                value_name: "seven".to_string(),
-                r: 255,
-                theta: 6000,
+                value: ScoreValue::Zone(7),
+                r: 25_500,
+                theta: 24_000,
             },
             MeasuredScore {
-                value: 6,
-                value_name: "six".to_string(),
-                r: 1000,
-                theta: 3000,
+                value: ScoreValue::Zone(6),
+                r: 100_000,
+                theta: 12_000,
             },
             MeasuredScore {
-                value: 5,
-                value_name: "five".to_string(),
-                r: 1500,
-                theta: 50,
+                value: ScoreValue::Zone(5),
+                r: 150_000,
+                theta: 5000,
             }
         ] );
         assert_eq!(
@@ -645,50 +1883,41 @@ mod tests {
     fn test_value_end_serialise() {
         let data = End::Scored( vec![
             ValueScore {
-                value: 7,
-                value_name: "seven".to_string(),
+                value: ScoreValue::Zone(7),
             },
             ValueScore {
-                value: 6,
-                value_name: "six".to_string(),
+                value: ScoreValue::Zone(6),
             },
             ValueScore {
-                value: 5,
-                value_name: "five".to_string(),
+                value: ScoreValue::Zone(5),
             }
         ] ).serialise().unwrap();
         assert_eq!(
             data,
-            vec![0, 3, 0,
-                7, 5, 0, 115, 101, 118, 101, 110,
-                6, 3, 0, 115, 105, 120,
-                5, 4, 0, 102, 105, 118, 101,
-            ]
+            vec![0, 4, 3, 7, 6, 5]
         )
     }
 
     #[test]
     fn test_value_end_deserialise() {
         let data = End::deserialise(
-            &mut Buffer::from(
-                vec![0, 3, 0,
+            &mut Reader::with_version(
+                &[0, 3, 0,
                         7, 5, 0, 115, 101, 118, 101, 110,
                         6, 3, 0, 115, 105, 120,
                         5, 4, 0, 102, 105, 118, 101,
-                ]
+                ],
+                Version::V0_1_0,
             )).unwrap();
         let s = End::Scored( vec![
             ValueScore {
-                value: 7,
-                value_name: "seven".to_string(),
+                value: ScoreValue::Zone(7),
             },
             ValueScore {
-                value: 6,
-                value_name: "six".to_string(),
+                value: ScoreValue::Zone(6),
             },
             ValueScore {
-                value: 5,
-                value_name: "five".to_string(),
+                value: ScoreValue::Zone(5),
             }
         ] );
         assert_eq!(
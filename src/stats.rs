@@ -0,0 +1,303 @@
+use std::ops::AddAssign;
+use crate::face::FaceKind;
+use crate::session::{End, Round, Session, Target};
+
+/// Totals and counts over some arrows
+///
+/// Counts are wide enough that adding up any number of ends cannot overflow them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub total: u64,
+    /// Scored arrows, including misses
+    pub arrows: usize,
+    pub hits: usize,
+    /// Arrows in the gold of the face, see `FaceKind::gold`
+    pub golds: usize,
+    /// Arrows in the highest scoring zone of the face, including Xs, so 10s on WA faces and 5s
+    /// on a Worcester face
+    pub tens: usize,
+    pub xs: usize,
+    pub misses: usize,
+    /// Arrows shot at a blank boss
+    pub blank: u64,
+    pub shot_trainer: u64,
+    pub bow_draws: u64,
+    pub bare_shafts: usize,
+}
+
+impl Summary {
+    /// Returns the average score of a scored arrow, or None if there were none
+    pub fn average(&self) -> Option<f64> {
+        if self.arrows == 0 {
+            None
+        } else {
+            Some(self.total as f64 / self.arrows as f64)
+        }
+    }
+}
+
+impl AddAssign<&Summary> for Summary {
+    fn add_assign(&mut self, other: &Summary) {
+        self.total += other.total;
+        self.arrows += other.arrows;
+        self.hits += other.hits;
+        self.golds += other.golds;
+        self.tens += other.tens;
+        self.xs += other.xs;
+        self.misses += other.misses;
+        self.blank += other.blank;
+        self.shot_trainer += other.shot_trainer;
+        self.bow_draws += other.bow_draws;
+        self.bare_shafts += other.bare_shafts;
+    }
+}
+
+/// Internal method for summarising the ends of any number of Targets, each on its own face
+fn summarise<'a>(targets: impl Iterator<Item = &'a Target>) -> Summary {
+    let mut res = Summary::default();
+    for target in targets {
+        res += &target.summary();
+    }
+    res
+}
+
+/// Internal method for the running total after each scoring end
+fn running_totals<'a>(ends: impl Iterator<Item = &'a End>) -> Vec<u64> {
+    let mut total = 0;
+    ends.filter(|end| matches!(end, End::Scored(_) | End::Measured(_)))
+        .map(|end| {
+            total += end.total();
+            total
+        })
+        .collect()
+}
+
+/// Internal method for the running total after each dozen scored arrows, and after the last
+/// arrow if it does not finish a dozen
+fn dozen_totals<'a>(ends: impl Iterator<Item = &'a End>) -> Vec<u64> {
+    let mut res = vec![];
+    let mut total = 0;
+    let mut arrows = 0;
+
+    for value in ends.flat_map(End::score_values) {
+        total += value.points() as u64;
+        arrows += 1;
+        if arrows % 12 == 0 {
+            res.push(total);
+        }
+    }
+    if arrows % 12 != 0 {
+        res.push(total);
+    }
+
+    res
+}
+
+impl End {
+    /// Returns the Summary of this End shot at the given face, where golds and tens are counted
+    /// as on the WA ten zone face if it is not known
+    pub fn summary(&self, face: Option<FaceKind>) -> Summary {
+        let face = face.unwrap_or(FaceKind::WaTenZone);
+        let mut res = Summary::default();
+
+        for value in self.score_values() {
+            let points = value.points();
+            res.total += points as u64;
+            res.arrows += 1;
+            if value.is_hit() {
+                res.hits += 1;
+            } else {
+                res.misses += 1;
+            }
+            if face.gold().is_some_and(|gold| points >= gold) {
+                res.golds += 1;
+            }
+            if points == face.max_value() {
+                res.tens += 1;
+            }
+            if value.is_x() {
+                res.xs += 1;
+            }
+        }
+
+        match self {
+            End::Blank(count) => res.blank = *count as u64,
+            End::ShotTrainer(count) => res.shot_trainer = *count as u64,
+            End::BowDraws(count) => res.bow_draws = *count as u64,
+            End::BareShaft(shafts) => res.bare_shafts = shafts.len(),
+            _ => {},
+        }
+
+        res
+    }
+
+    /// Returns the points scored in this End, nothing for ends which are not scored
+    pub fn total(&self) -> u64 {
+        self.summary(None).total
+    }
+}
+
+impl Target {
+    pub fn summary(&self) -> Summary {
+        let mut res = Summary::default();
+        for end in &self.ends {
+            res += &end.summary(self.face);
+        }
+        res
+    }
+
+    pub fn total(&self) -> u64 {
+        self.summary().total
+    }
+
+    /// Returns the running total after each Scored or Measured end
+    pub fn running_totals(&self) -> Vec<u64> {
+        running_totals(self.ends.iter())
+    }
+
+    /// Returns the running total after each dozen arrows, and after the last if it does not
+    /// finish a dozen
+    pub fn dozen_totals(&self) -> Vec<u64> {
+        dozen_totals(self.ends.iter())
+    }
+}
+
+impl Round {
+    /// Returns every End of every Target, in order
+    pub fn ends(&self) -> impl Iterator<Item = &End> {
+        self.targets.iter().flat_map(|target| target.ends.iter())
+    }
+
+    pub fn summary(&self) -> Summary {
+        summarise(self.targets.iter())
+    }
+
+    pub fn total(&self) -> u64 {
+        self.summary().total
+    }
+
+    /// Returns the running total after each Scored or Measured end, carried across Targets
+    pub fn running_totals(&self) -> Vec<u64> {
+        running_totals(self.ends())
+    }
+
+    /// Returns the running total after each dozen arrows, carried across Targets
+    pub fn dozen_totals(&self) -> Vec<u64> {
+        dozen_totals(self.ends())
+    }
+}
+
+impl Session {
+    /// Returns every End of every Round, in order
+    pub fn ends(&self) -> impl Iterator<Item = &End> {
+        self.rounds.iter().flat_map(Round::ends)
+    }
+
+    pub fn summary(&self) -> Summary {
+        summarise(self.rounds.iter().flat_map(|round| round.targets.iter()))
+    }
+
+    pub fn total(&self) -> u64 {
+        self.summary().total
+    }
+
+    /// Returns the running total after each Scored or Measured end, carried across Rounds
+    pub fn running_totals(&self) -> Vec<u64> {
+        running_totals(self.ends())
+    }
+
+    /// Returns the running total after each dozen arrows, carried across Rounds
+    pub fn dozen_totals(&self) -> Vec<u64> {
+        dozen_totals(self.ends())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rounds;
+    use crate::session::{ScoreValue, ValueScore};
+
+    fn end(values: &[ScoreValue]) -> End {
+        End::Scored(values.iter().map(|value| ValueScore { value: *value }).collect())
+    }
+
+    #[test]
+    fn test_end_summary() {
+        let summary = end(&[ScoreValue::X(10), ScoreValue::Zone(10), ScoreValue::Zone(9), ScoreValue::Zone(7), ScoreValue::Miss, ScoreValue::Hit]).summary(None);
+
+        assert_eq!(
+            Summary {
+                total: 36,
+                arrows: 6,
+                hits: 5,
+                golds: 3,
+                tens: 2,
+                xs: 1,
+                misses: 1,
+                ..Default::default()
+            },
+            summary
+        );
+        assert_eq!(Some(6.0), summary.average());
+        assert_eq!(0, End::Blank(6).total());
+        assert_eq!(None, Summary::default().average());
+    }
+
+    #[test]
+    fn test_round_summary() {
+        let mut round = rounds::find("National").unwrap().new_round();
+        for target in &mut round.targets {
+            target.ends.push(End::BowDraws(20));
+            for _ in 0..5 {
+                target.ends.push(end(&[ScoreValue::Zone(9), ScoreValue::Zone(7), ScoreValue::Zone(5)]));
+            }
+            target.ends.push(End::Blank(6));
+        }
+
+        let summary = round.summary();
+        assert_eq!(210, summary.total);
+        assert_eq!(30, summary.arrows);
+        assert_eq!(10, summary.golds);
+        assert_eq!(40, summary.bow_draws);
+        assert_eq!(12, summary.blank);
+
+        assert_eq!(vec![21, 42, 63, 84, 105], round.targets[0].running_totals());
+        assert_eq!(vec![21, 42, 63, 84, 105, 126, 147, 168, 189, 210], round.running_totals());
+        assert_eq!(vec![84, 168, 210], round.dozen_totals());
+        assert_eq!(vec![84, 105], round.targets[1].dozen_totals());
+        assert_eq!(105, round.targets[1].total());
+    }
+
+    #[test]
+    fn test_face_golds() {
+        let mut round = rounds::find("Worcester").unwrap().new_round();
+        round.targets[0].ends.push(end(&[ScoreValue::Zone(5), ScoreValue::Zone(5), ScoreValue::Zone(4), ScoreValue::Zone(3), ScoreValue::Miss]));
+
+        let summary = round.summary();
+        assert_eq!(2, summary.golds);
+        assert_eq!(2, summary.tens);
+
+        let mut round = rounds::find("WA Field").unwrap().new_round();
+        round.targets[0].ends.push(end(&[ScoreValue::X(6), ScoreValue::Zone(5), ScoreValue::Zone(4)]));
+
+        let summary = round.summary();
+        assert_eq!(2, summary.golds);
+        assert_eq!(1, summary.tens);
+        assert_eq!(1, summary.xs);
+
+        let mut round = rounds::find("WA 3D").unwrap().new_round();
+        round.targets[0].ends.push(end(&[ScoreValue::Zone(11), ScoreValue::Zone(10)]));
+        assert_eq!(0, round.summary().golds);
+    }
+
+    #[test]
+    fn test_summary_overflow() {
+        let mut target = rounds::find("Portsmouth").unwrap().new_round().targets.remove(0);
+        target.ends = vec![End::Blank(u32::MAX), End::Blank(1), End::BowDraws(u32::MAX), End::BowDraws(u32::MAX)];
+
+        let summary = target.summary();
+        assert_eq!(u32::MAX as u64 + 1, summary.blank);
+        assert_eq!(2 * u32::MAX as u64, summary.bow_draws);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::archer::Handedness;
+use crate::bow::BowStyle;
+use crate::session::{Session, Target};
+use crate::units::Length;
+
+/// How far in millimetres the bare shafts may land from the fletched arrows before a change is
+/// suggested
+pub const DEFAULT_TOLERANCE_MM: f64 = 20.0;
+
+/// What to do with the nocking point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NockAdvice {
+    Good,
+    /// Bare shafts land low, so the nocking point should come down
+    TooHigh,
+    /// Bare shafts land high, so the nocking point should go up
+    TooLow,
+}
+
+/// What the horizontal offset says about the arrows' spine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpineAdvice {
+    Good,
+    TooStiff,
+    TooWeak,
+}
+
+/// Where the bare shafts at a Target landed compared to the fletched arrows, and what that
+/// suggests
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BareShaftReport {
+    pub distance: Length,
+    /// Centre of the bare shafts less the centre of the fletched arrows, in millimetres right
+    /// and up
+    pub offset_mm: (f64, f64),
+    pub nock: NockAdvice,
+    /// None when shot with a release aid, where the reading depends on the rest and setup
+    pub spine: Option<SpineAdvice>,
+}
+
+/// Reads bare-shaft groups against fletched groups shot at the same Target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tuner {
+    pub handedness: Handedness,
+    pub release_aid: bool,
+    pub tolerance_mm: f64,
+}
+
+impl Tuner {
+    /// Returns a Tuner for a finger release with the default tolerance
+    pub fn new(handedness: Handedness) -> Tuner {
+        Tuner {
+            handedness,
+            release_aid: false,
+            tolerance_mm: DEFAULT_TOLERANCE_MM,
+        }
+    }
+
+    /// Returns a Tuner for the archer and bow of a Session, assuming a right-handed archer if
+    /// unknown
+    pub fn for_session(session: &Session) -> Tuner {
+        let handedness = session.archer.as_ref()
+            .and_then(|archer| archer.handedness)
+            .unwrap_or(Handedness::Right);
+        let release_aid = session.bow.as_ref()
+            .is_some_and(|bow| bow.style == BowStyle::Compound || bow.release_aid.is_some());
+
+        Tuner {
+            release_aid,
+            ..Tuner::new(handedness)
+        }
+    }
+
+    /// Compares the bare shafts at a Target with its measured arrows, or returns None unless it
+    /// has both
+    pub fn analyse(&self, target: &Target) -> Option<BareShaftReport> {
+        let (fx, fy) = target.group().centre()?;
+        let (bx, by) = target.bare_shaft_group().centre()?;
+        let (dx, dy) = (bx - fx, by - fy);
+
+        let nock = if dy < -self.tolerance_mm {
+            NockAdvice::TooHigh
+        } else if dy > self.tolerance_mm {
+            NockAdvice::TooLow
+        } else {
+            NockAdvice::Good
+        };
+
+        let spine = if self.release_aid {
+            None
+        } else {
+            // for a right-handed finger release, bare shafts left of the group are stiff
+            let dx = match self.handedness {
+                Handedness::Right => dx,
+                Handedness::Left => -dx,
+            };
+
+            Some(if dx < -self.tolerance_mm {
+                SpineAdvice::TooStiff
+            } else if dx > self.tolerance_mm {
+                SpineAdvice::TooWeak
+            } else {
+                SpineAdvice::Good
+            })
+        };
+
+        Some(BareShaftReport {
+            distance: target.distance,
+            offset_mm: (dx, dy),
+            nock,
+            spine,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archer::Archer;
+    use crate::bow::Bow;
+    use crate::session::{BareShaft, End, MeasuredScore, ScoreValue};
+    use crate::units::LengthUnit;
+
+    /// Returns a Target with a fletched group at the centre and bare shafts at the given position
+    fn target(r: u32, theta: u32) -> Target {
+        Target {
+            ends: vec![
+                End::Measured(vec![
+                    MeasuredScore { value: ScoreValue::Zone(10), r: 500, theta: 0 },
+                    MeasuredScore { value: ScoreValue::Zone(10), r: 500, theta: 18000 },
+                ]),
+                End::BareShaft(vec![BareShaft { r, theta }, BareShaft { r, theta }]),
+            ],
+            ..Target::new(
+                "Tuning".to_string(),
+                Length::new(18, LengthUnit::Metres),
+                Length::new(40, LengthUnit::Centimetres),
+            )
+        }
+    }
+
+    #[test]
+    fn test_analyse() {
+        let tuner = Tuner::new(Handedness::Right);
+
+        let report = tuner.analyse(&target(5000, 27000)).unwrap();
+        assert_eq!(NockAdvice::TooHigh, report.nock);
+        assert_eq!(Some(SpineAdvice::Good), report.spine);
+        assert!((report.offset_mm.1 + 50.0).abs() < 1e-9);
+
+        let report = tuner.analyse(&target(5000, 18000)).unwrap();
+        assert_eq!(NockAdvice::Good, report.nock);
+        assert_eq!(Some(SpineAdvice::TooStiff), report.spine);
+
+        let report = Tuner::new(Handedness::Left).analyse(&target(5000, 18000)).unwrap();
+        assert_eq!(Some(SpineAdvice::TooWeak), report.spine);
+
+        let report = tuner.analyse(&target(1000, 9000)).unwrap();
+        assert_eq!((NockAdvice::Good, Some(SpineAdvice::Good)), (report.nock, report.spine));
+
+        let mut bare = target(5000, 9000);
+        bare.ends.remove(0);
+        assert_eq!(None, tuner.analyse(&bare));
+    }
+
+    #[test]
+    fn test_for_session() {
+        let mut session = Session {
+            bow: Some(Bow::new(BowStyle::Compound)),
+            archer: Some(Archer {
+                handedness: Some(Handedness::Left),
+                ..Archer::new("Robin".to_string())
+            }),
+            ..Session::new("Home".to_string())
+        };
+
+        let tuner = Tuner::for_session(&session);
+        assert_eq!(Handedness::Left, tuner.handedness);
+        assert!(tuner.release_aid);
+        assert_eq!(None, tuner.analyse(&target(5000, 9000)).unwrap().spine);
+
+        session.bow = None;
+        session.archer = None;
+        assert_eq!(Tuner::new(Handedness::Right), Tuner::for_session(&session));
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt;
+use crate::buffer::{BufferError, Reader};
+use crate::Serialisable;
+
+/// A unit of length, for distances to a target and sizes of target faces
+#[derive(Serialisable, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LengthUnit {
+    #[oaf(tag = 0)]
+    Metres,
+    #[oaf(tag = 1)]
+    Yards,
+    #[oaf(tag = 2)]
+    Feet,
+    #[oaf(tag = 3)]
+    Centimetres,
+    #[oaf(tag = 4)]
+    Inches,
+    #[oaf(tag = 5)]
+    Millimetres,
+}
+
+impl LengthUnit {
+    /// Returns the length of one of this unit in micrometres, which every unit is an exact
+    /// whole number of
+    pub fn micrometres(&self) -> u64 {
+        match self {
+            LengthUnit::Metres => 1_000_000,
+            LengthUnit::Yards => 914_400,
+            LengthUnit::Feet => 304_800,
+            LengthUnit::Centimetres => 10_000,
+            LengthUnit::Inches => 25_400,
+            LengthUnit::Millimetres => 1_000,
+        }
+    }
+
+    /// Returns the short symbol for this unit, as written before format 0.6.0
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            LengthUnit::Metres => "m",
+            LengthUnit::Yards => "yd",
+            LengthUnit::Feet => "ft",
+            LengthUnit::Centimetres => "cm",
+            LengthUnit::Inches => "in",
+            LengthUnit::Millimetres => "mm",
+        }
+    }
+
+    /// Reads a unit from a symbol or name, ignoring case, surrounding space and a trailing full
+    /// stop, so "m", "Metres", "meters" and "M." are all metres
+    pub fn parse(text: &str) -> Option<LengthUnit> {
+        let text = text.trim().trim_end_matches('.').to_ascii_lowercase();
+
+        match text.as_str() {
+            "m" | "metre" | "metres" | "meter" | "meters" => Some(LengthUnit::Metres),
+            "yd" | "yds" | "yard" | "yards" => Some(LengthUnit::Yards),
+            "ft" | "foot" | "feet" => Some(LengthUnit::Feet),
+            "cm" | "centimetre" | "centimetres" | "centimeter" | "centimeters" => Some(LengthUnit::Centimetres),
+            "in" | "inch" | "inches" => Some(LengthUnit::Inches),
+            "mm" | "millimetre" | "millimetres" | "millimeter" | "millimeters" => Some(LengthUnit::Millimetres),
+            _ => None,
+        }
+    }
+
+    /// Reads a unit written as a string before format 0.6.0, where an empty string or one which
+    /// cannot be recognised means the given default, as any string was allowed then
+    pub fn deserialise_legacy(default: LengthUnit, data: &mut Reader) -> Result<LengthUnit, BufferError> {
+        let text = data.pop_string()?;
+
+        Ok(LengthUnit::parse(&text).unwrap_or(default))
+    }
+}
+
+impl fmt::Display for LengthUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// A whole number of some unit of length
+///
+/// Equality compares the value and unit as written, so 1 m is not equal to 100 cm; compare
+/// `micrometres` to compare the lengths themselves
+#[derive(Serialisable, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Length {
+    pub value: u32,
+    pub unit: LengthUnit,
+}
+
+impl Length {
+    pub fn new(value: u32, unit: LengthUnit) -> Length {
+        Length {
+            value,
+            unit,
+        }
+    }
+
+    /// Returns this length in micrometres, exactly
+    pub fn micrometres(&self) -> u64 {
+        self.value as u64 * self.unit.micrometres()
+    }
+
+    /// Returns this length in the given unit
+    pub fn to(&self, unit: LengthUnit) -> f64 {
+        self.micrometres() as f64 / unit.micrometres() as f64
+    }
+
+    /// Returns this length in the given unit if it is a whole number of that unit
+    pub fn convert(&self, unit: LengthUnit) -> Option<Length> {
+        let micrometres = self.micrometres();
+        if !micrometres.is_multiple_of(unit.micrometres()) {
+            return None;
+        }
+
+        let value = u32::try_from(micrometres / unit.micrometres()).ok()?;
+        Some(Length::new(value, unit))
+    }
+
+    pub fn metres(&self) -> f64 {
+        self.to(LengthUnit::Metres)
+    }
+
+    pub fn centimetres(&self) -> f64 {
+        self.to(LengthUnit::Centimetres)
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.value, self.unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer::Buffer;
+
+    #[test]
+    fn test_conversions() {
+        let twenty_yards = Length::new(20, LengthUnit::Yards);
+        assert_eq!(18.288, twenty_yards.metres());
+        assert_eq!(Some(Length::new(60, LengthUnit::Feet)), twenty_yards.convert(LengthUnit::Feet));
+        assert_eq!(None, twenty_yards.convert(LengthUnit::Metres));
+
+        assert_eq!(Some(Length::new(10, LengthUnit::Inches)), Length::new(254, LengthUnit::Millimetres).convert(LengthUnit::Inches));
+        assert_eq!(Some(Length::new(1220, LengthUnit::Millimetres)), Length::new(122, LengthUnit::Centimetres).convert(LengthUnit::Millimetres));
+        assert!(Length::new(18, LengthUnit::Metres).micrometres() < Length::new(20, LengthUnit::Yards).micrometres());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Some(LengthUnit::Metres), LengthUnit::parse("m"));
+        assert_eq!(Some(LengthUnit::Metres), LengthUnit::parse(" Metres "));
+        assert_eq!(Some(LengthUnit::Metres), LengthUnit::parse("meters"));
+        assert_eq!(Some(LengthUnit::Metres), LengthUnit::parse("M."));
+        assert_eq!(Some(LengthUnit::Yards), LengthUnit::parse("yds"));
+        assert_eq!(Some(LengthUnit::Inches), LengthUnit::parse("inches"));
+        assert_eq!(Some(LengthUnit::Centimetres), LengthUnit::parse("CM"));
+        assert_eq!(None, LengthUnit::parse("furlongs"));
+
+        for unit in [LengthUnit::Metres, LengthUnit::Yards, LengthUnit::Feet, LengthUnit::Centimetres, LengthUnit::Inches, LengthUnit::Millimetres] {
+            assert_eq!(Some(unit), LengthUnit::parse(unit.symbol()));
+        }
+    }
+
+    #[test]
+    fn test_deserialise_legacy() {
+        let mut buf = Buffer::new();
+        buf.append_string(&"".to_string()).unwrap();
+        buf.append_string(&"Yards".to_string()).unwrap();
+        buf.append_string(&"furlongs".to_string()).unwrap();
+        let mut reader = buf.reader();
+
+        assert_eq!(Ok(LengthUnit::Metres), LengthUnit::deserialise_legacy(LengthUnit::Metres, &mut reader));
+        assert_eq!(Ok(LengthUnit::Yards), LengthUnit::deserialise_legacy(LengthUnit::Metres, &mut reader));
+        assert_eq!(Ok(LengthUnit::Metres), LengthUnit::deserialise_legacy(LengthUnit::Metres, &mut reader));
+    }
+
+    #[test]
+    fn test_length_serialise() {
+        let length = Length::new(122, LengthUnit::Centimetres);
+        let data = length.serialise().unwrap();
+
+        assert_eq!(data, vec![122, 0, 0, 0, 3]);
+        assert_eq!(length, Length::deserialise(&mut data.reader()).unwrap());
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+
+/// A version of the file format, written after the magic bytes of every file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u8,
+    pub minor: u8,
+    pub patch: u8,
+}
+
+impl Version {
+    /// Lengths and counts are fixed two byte integers
+    pub const V0_1_0: Version = Version::new(0, 1, 0);
+    /// Lengths and counts are LEB128 variable length integers
+    pub const V0_2_0: Version = Version::new(0, 2, 0);
+    /// The body is followed by its CRC-32C checksum
+    pub const V0_3_0: Version = Version::new(0, 3, 0);
+    /// The body is made of tagged, length-prefixed chunks, and every End carries its length
+    pub const V0_4_0: Version = Version::new(0, 4, 0);
+    /// The date of a Session is a calendar date with optional times, rather than free text
+    pub const V0_5_0: Version = Version::new(0, 5, 0);
+    /// Units of length are one byte tags rather than free text
+    pub const V0_6_0: Version = Version::new(0, 6, 0);
+    /// The value of an arrow is a one byte ScoreValue rather than points and free text
+    pub const V0_7_0: Version = Version::new(0, 7, 0);
+    /// Arrow positions are in hundredths of a millimetre and of a degree rather than whole ones
+    pub const V0_8_0: Version = Version::new(0, 8, 0);
+
+    /// The newest version this library understands, and the one it writes by default
+    pub const CURRENT: Version = Version::V0_8_0;
+
+    /// Every version this library can read and write, oldest first
+    pub const SUPPORTED: &'static [Version] = &[
+        Version::V0_1_0,
+        Version::V0_2_0,
+        Version::V0_3_0,
+        Version::V0_4_0,
+        Version::V0_5_0,
+        Version::V0_6_0,
+        Version::V0_7_0,
+        Version::V0_8_0,
+    ];
+
+    pub const fn new(major: u8, minor: u8, patch: u8) -> Version {
+        Version {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn is_supported(&self) -> bool {
+        Version::SUPPORTED.contains(self)
+    }
+
+    /// Returns the number of bytes following the body of a file in this version
+    pub fn footer_length(&self) -> usize {
+        if *self < Version::V0_3_0 {
+            0
+        } else {
+            4
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
@@ -0,0 +1,195 @@
+use crate::date::Date;
+use crate::session::Session;
+use crate::stats::Summary;
+
+/// A span of time training is totalled over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Period {
+    Day,
+    /// A week starting on a Monday
+    Week,
+    Month,
+}
+
+impl Period {
+    /// Returns the first day of the period holding a date
+    pub fn start(&self, date: Date) -> Date {
+        match self {
+            Period::Day => date,
+            Period::Week => Date::from_days_since_epoch(date.days_since_epoch() - date.weekday() as i64)
+                .unwrap_or(date),
+            Period::Month => Date::new(date.year(), date.month(), 1).unwrap_or(date),
+        }
+    }
+}
+
+/// How much each kind of activity adds to the training load, per arrow, rep or draw
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weights {
+    pub scored: f64,
+    pub blank: f64,
+    pub bare_shaft: f64,
+    pub shot_trainer: f64,
+    pub bow_draw: f64,
+}
+
+impl Default for Weights {
+    /// Every arrow shot counts fully, and shot trainer reps and bow draws count half
+    fn default() -> Self {
+        Weights {
+            scored: 1.0,
+            blank: 1.0,
+            bare_shaft: 1.0,
+            shot_trainer: 0.5,
+            bow_draw: 0.5,
+        }
+    }
+}
+
+/// Training done in one period
+#[derive(Debug, Clone, PartialEq)]
+pub struct Volume {
+    /// The first day of the period
+    pub start: Date,
+    pub sessions: usize,
+    pub scored: usize,
+    pub blank: u64,
+    pub bare_shafts: usize,
+    pub shot_trainer: u64,
+    pub bow_draws: u64,
+    /// The weighted sum of everything above but the number of sessions
+    pub load: f64,
+}
+
+impl Volume {
+    /// Returns the number of arrows shot, scored or not
+    pub fn arrows(&self) -> u64 {
+        self.scored as u64 + self.blank + self.bare_shafts as u64
+    }
+
+    /// Internal method for adding a Session's activity to this period
+    fn add(&mut self, summary: &Summary, weights: &Weights) {
+        self.sessions += 1;
+        self.scored += summary.arrows;
+        self.blank += summary.blank;
+        self.bare_shafts += summary.bare_shafts;
+        self.shot_trainer += summary.shot_trainer;
+        self.bow_draws += summary.bow_draws;
+        self.load += summary.arrows as f64 * weights.scored
+            + summary.blank as f64 * weights.blank
+            + summary.bare_shafts as f64 * weights.bare_shaft
+            + summary.shot_trainer as f64 * weights.shot_trainer
+            + summary.bow_draws as f64 * weights.bow_draw;
+    }
+}
+
+/// Returns the training done in each period holding a Session, oldest first, leaving out
+/// Sessions without a date
+pub fn report(sessions: &[Session], period: Period, weights: &Weights) -> Vec<Volume> {
+    let mut res: Vec<Volume> = vec![];
+
+    for session in sessions {
+        let Some(date) = session.date else {
+            continue;
+        };
+        let start = period.start(date.date);
+
+        let i = match res.iter().position(|volume| volume.start == start) {
+            Some(i) => i,
+            None => {
+                res.push(Volume {
+                    start,
+                    sessions: 0,
+                    scored: 0,
+                    blank: 0,
+                    bare_shafts: 0,
+                    shot_trainer: 0,
+                    bow_draws: 0,
+                    load: 0.0,
+                });
+                res.len() - 1
+            },
+        };
+        res[i].add(&session.summary(), weights);
+    }
+
+    res.sort_by_key(|volume| volume.start);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::SessionDate;
+    use crate::session::{End, Round, ScoreValue, ValueScore};
+    use crate::rounds;
+
+    fn date(year: u16, month: u8, day: u8) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    fn session(date: Option<Date>, ends: Vec<End>) -> Session {
+        let mut round: Round = rounds::find("Portsmouth").unwrap().new_round();
+        round.targets[0].ends = ends;
+
+        Session {
+            date: date.map(SessionDate::new),
+            rounds: vec![round],
+            ..Session::new("Home".to_string())
+        }
+    }
+
+    #[test]
+    fn test_period_start() {
+        // a Wednesday
+        let day = date(2024, 1, 3);
+        assert_eq!(day, Period::Day.start(day));
+        assert_eq!(date(2024, 1, 1), Period::Week.start(day));
+        assert_eq!(date(2023, 12, 25), Period::Week.start(date(2023, 12, 31)));
+        assert_eq!(date(2024, 1, 1), Period::Month.start(day));
+    }
+
+    #[test]
+    fn test_report() {
+        let scored = || End::Scored(vec![ValueScore { value: ScoreValue::Zone(9) }; 3]);
+        let sessions = vec![
+            session(Some(date(2024, 1, 10)), vec![End::BowDraws(20), scored(), End::Blank(6)]),
+            session(Some(date(2024, 1, 2)), vec![End::ShotTrainer(30), scored()]),
+            session(Some(date(2024, 1, 3)), vec![scored(), scored()]),
+            session(None, vec![scored()]),
+        ];
+
+        let weeks = report(&sessions, Period::Week, &Weights::default());
+        assert_eq!(2, weeks.len());
+        assert_eq!(date(2024, 1, 1), weeks[0].start);
+        assert_eq!(2, weeks[0].sessions);
+        assert_eq!(9, weeks[0].scored);
+        assert_eq!(30, weeks[0].shot_trainer);
+        assert_eq!(24.0, weeks[0].load);
+        assert_eq!(date(2024, 1, 8), weeks[1].start);
+        assert_eq!(9, weeks[1].arrows());
+        assert_eq!(19.0, weeks[1].load);
+
+        let months = report(&sessions, Period::Month, &Weights { bow_draw: 0.0, ..Default::default() });
+        assert_eq!(1, months.len());
+        assert_eq!(12, months[0].scored);
+        assert_eq!(20, months[0].bow_draws);
+        assert_eq!(33.0, months[0].load);
+
+        assert_eq!(3, report(&sessions, Period::Day, &Weights::default()).len());
+    }
+
+    #[test]
+    fn test_report_overflow() {
+        let day = Some(date(2024, 1, 3));
+        let sessions = vec![
+            session(day, vec![End::Blank(u32::MAX), End::BowDraws(u32::MAX)]),
+            session(day, vec![End::Blank(u32::MAX), End::BowDraws(u32::MAX)]),
+        ];
+
+        let days = report(&sessions, Period::Day, &Weights::default());
+        assert_eq!(2 * u32::MAX as u64, days[0].blank);
+        assert_eq!(2 * u32::MAX as u64, days[0].bow_draws);
+        assert_eq!(2 * u32::MAX as u64, days[0].arrows());
+    }
+}